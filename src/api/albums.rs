@@ -1,43 +1,84 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::Json;
 use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 
 use crate::api::base::BaseApi;
-use crate::models::{album::*, error::ApiError};
+use crate::models::{
+    album::*,
+    batch::{BatchRequest, BatchResponse},
+    error::ApiError,
+    results::ResultList,
+    search::{EntityType, SearchDetail, SearchHit},
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::filters::SearchFilters;
+use crate::utils::formatting;
+use crate::utils::validation::{self, Validate, ValidatedJson, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct SearchAlbumsQuery {
     query: String,
     limit: Option<usize>,
+    /// Fail the whole request if any result cannot be resolved
+    strict: Option<bool>,
+    /// `none` or `basic` return search hits without per-result detail calls (default `full`)
+    #[param(inline)]
+    detail: Option<SearchDetail>,
+}
+
+impl Validate for SearchAlbumsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.query = validation::validate_query("query", &self.query)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct AlbumInfoQuery {
-    seokey: String,
+    seokey: Option<String>,
+    /// Numeric `album_id`, as an alternative to `seokey`
+    id: Option<String>,
+}
+
+impl Validate for AlbumInfoQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum AlbumResponse {
-    Albums(Vec<Album>),
-    Album(Album),
-    Error(ApiError),
+    Albums(ResultList<Album>),
+    Hits(ResultList<SearchHit>),
+    Album(Box<Album>),
 }
 
 /// Search for albums by name
 #[utoipa::path(
     get,
     path = "/albums/search",
-    params(SearchAlbumsQuery),
+    params(SearchAlbumsQuery, SearchFilters, FieldsQuery, IncludeQuery),
     responses(
-        (status = 200, description = "List of albums matching the search query", body = Vec<Album>),
-        (status = 404, description = "No albums found", body = ApiError)
+        (status = 200, description = "List of albums matching the search query (search hits with detail=none|basic); include=missing wraps them as { results, missing }", body = [Album]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No albums found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "albums"
 )]
 pub async fn search_albums(
-    Query(params): Query<SearchAlbumsQuery>,
-) -> Result<Json<AlbumResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<SearchAlbumsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+    ValidatedQuery(filters): ValidatedQuery<SearchFilters>,
+) -> Result<Json<AlbumResponse>, ApiError> {
+    let detail = params.detail.unwrap_or_default();
+    let limit = params.limit.unwrap_or(10);
+    filters.check_detail(detail)?;
     let api = BaseApi::new();
 
     // Use the exact same endpoint as the Python version
@@ -47,135 +88,160 @@ pub async fn search_albums(
     );
 
     eprintln!("Trying album search endpoint: {}", search_url);
-    match api.make_request_flexible(&search_url).await {
-        Ok(response) => {
-            eprintln!("Got album search response, extracting album IDs...");
-
-            // Extract album seokeys from search response like the Python version does
-            let mut album_ids = Vec::new();
-            let limit = params.limit.unwrap_or(10);
-
-            // Parse the search response to get seokeys - same structure as songs
-            if let Some(gr) = response.get("gr") {
-                if let Some(gr_array) = gr.as_array() {
-                    if let Some(first_group) = gr_array.first() {
-                        if let Some(gd) = first_group.get("gd") {
-                            if let Some(gd_array) = gd.as_array() {
-                                for (i, item) in gd_array.iter().enumerate() {
-                                    if i >= limit {
-                                        break;
-                                    }
-                                    if let Some(seo) = item.get("seo") {
-                                        if let Some(seo_str) = seo.as_str() {
-                                            album_ids.push(seo_str.to_string());
-                                        }
-                                    }
+    let response = api.make_request_flexible(&search_url).await.map_err(|e| {
+        eprintln!("Album search request error: {}", e);
+        e
+    })?;
+    eprintln!("Got album search response, extracting album IDs...");
+
+    if detail != SearchDetail::Full {
+        let hits = api.search_hits(
+            formatting::first_search_group(&response),
+            EntityType::Album,
+            detail,
+            limit,
+            include.wants_missing(),
+        );
+        if hits.is_empty() {
+            return Err(ApiError::not_found(&params.query));
+        }
+        return Ok(Json(AlbumResponse::Hits(hits)));
+    }
+
+    // Extract album seokeys from search response like the Python version does
+    let mut album_ids = Vec::new();
+    let candidates = filters.candidates(limit);
+
+    // Parse the search response to get seokeys - same structure as songs
+    if let Some(gr) = response.get("gr") {
+        if let Some(gr_array) = gr.as_array() {
+            if let Some(first_group) = gr_array.first() {
+                if let Some(gd) = first_group.get("gd") {
+                    if let Some(gd_array) = gd.as_array() {
+                        for (i, item) in gd_array.iter().enumerate() {
+                            if i >= candidates {
+                                break;
+                            }
+                            if let Some(seo) = item.get("seo") {
+                                if let Some(seo_str) = seo.as_str() {
+                                    album_ids.push(seo_str.to_string());
                                 }
                             }
                         }
                     }
                 }
             }
+        }
+    }
 
-            if album_ids.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No albums found for the given query")),
-                ));
-            }
-
-            eprintln!("Found {} album IDs: {:?}", album_ids.len(), album_ids);
-
-            // Now get album info for each seokey, just like Python version
-            let mut albums = Vec::new();
-            for album_id in album_ids {
-                let detail_url = format!(
-                    "https://gaana.com/apiv2?type=albumDetail&seokey={}",
-                    urlencoding::encode(&album_id)
-                );
-
-                eprintln!("Getting details for album: {}", album_id);
-                match api.make_request_flexible(&detail_url).await {
-                    Ok(detail_response) => {
-                        if let Some(processed_album) =
-                            api.process_gaana_album_response(&detail_response, false)
-                        {
-                            albums.push(processed_album);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to get details for album {}: {}", album_id, e);
-                        // Continue with other albums
-                    }
-                }
-            }
-
-            if albums.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No valid album data found")),
-                ));
-            }
+    if album_ids.is_empty() {
+        return Err(ApiError::not_found("No albums found for the given query"));
+    }
 
-            Ok(Json(AlbumResponse::Albums(albums)))
+    eprintln!("Found {} album IDs: {:?}", album_ids.len(), album_ids);
+
+    // Now get album info for each seokey, just like Python version
+    // Explicit, duration and genre filters look at each album's tracks
+    let needs_tracks = filters.needs_tracks();
+    let api = &api;
+    let mut albums = api
+        .fan_out(album_ids, |seokey| async move {
+            api.fetch_album(&seokey, needs_tracks).await
+        })
+        .await
+        .check_strict(params.strict.unwrap_or(false))?;
+    filters.apply(&mut albums.results, limit);
+    if needs_tracks {
+        for album in &mut albums.results {
+            album.tracks = None;
         }
-        Err(e) => {
-            eprintln!("Album search request error: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ));
+    }
+
+    if albums.is_empty() {
+        if !filters.is_empty() {
+            return Err(ApiError::not_found("No albums match the given filters"));
         }
+        return Err(ApiError::not_found("No valid album data found"));
     }
+
+    Ok(Json(AlbumResponse::Albums(
+        albums.with_missing(include.wants_missing()),
+    )))
 }
 
 /// Get detailed information about a specific album
 #[utoipa::path(
     get,
     path = "/albums/info",
-    params(AlbumInfoQuery),
+    params(AlbumInfoQuery, FieldsQuery),
     responses(
         (status = 200, description = "Detailed information about the album", body = Album),
-        (status = 404, description = "Album not found", body = ApiError)
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Album not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "albums"
 )]
 pub async fn get_album_info(
-    Query(params): Query<AlbumInfoQuery>,
-) -> Result<Json<AlbumResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<AlbumInfoQuery>,
+) -> Result<Json<AlbumResponse>, ApiError> {
     let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Album,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
 
     let url = format!(
         "https://gaana.com/apiv2?type=albumDetail&seokey={}",
-        urlencoding::encode(&params.seokey)
+        urlencoding::encode(&seokey)
     );
 
     eprintln!("Getting album info from: {}", url);
-    match api.make_request_flexible(&url).await {
-        Ok(response) => {
-            eprintln!("Parsing album info response...");
+    let response = api.make_request_flexible(&url).await.map_err(|e| {
+        eprintln!("Album info request failed: {}", e);
+        e
+    })?;
+    eprintln!("Parsing album info response...");
+
+    if let Some(processed_album) = api.process_gaana_album_response(&response, true) {
+        return Ok(Json(AlbumResponse::Album(Box::new(processed_album))));
+    }
 
-            if let Some(processed_album) = api.process_gaana_album_response(&response, true) {
-                return Ok(Json(AlbumResponse::Album(processed_album)));
-            }
+    eprintln!("No valid album found in response");
+    eprintln!(
+        "Response structure: {}",
+        serde_json::to_string_pretty(&response).unwrap_or_default()
+    );
 
-            eprintln!("No valid album found in response");
-            eprintln!(
-                "Response structure: {}",
-                serde_json::to_string_pretty(&response).unwrap_or_default()
-            );
+    Err(ApiError::invalid_seokey(&seokey))
+}
 
-            Err((
-                StatusCode::NOT_FOUND,
-                Json(ApiError::invalid_seokey(&params.seokey)),
-            ))
-        }
-        Err(e) => {
-            eprintln!("Album info request failed: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ))
-        }
-    }
+/// Look up many albums at once by seokey or id
+#[utoipa::path(
+    post,
+    path = "/albums/batch",
+    params(FieldsQuery),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Albums keyed by the requested seokey or id, with failures under `missing`", body = AlbumBatch),
+        (status = 400, description = "Invalid request body", body = ApiError)
+    ),
+    tag = "albums"
+)]
+pub async fn batch_albums(
+    ValidatedJson(request): ValidatedJson<BatchRequest>,
+) -> Json<BatchResponse<Album>> {
+    let api = BaseApi::new();
+    let api = &api;
+    Json(
+        api.fetch_batch(EntityType::Album, request, |seokey| async move {
+            api.fetch_album_cached(&seokey).await
+        })
+        .await,
+    )
 }
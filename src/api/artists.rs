@@ -1,43 +1,214 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::Json;
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use utoipa::IntoParams;
+use std::collections::HashSet;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::api::base::BaseApi;
-use crate::models::{artist::*, error::ApiError};
+use crate::models::{
+    album::Album,
+    artist::*,
+    batch::{BatchRequest, BatchResponse},
+    error::ApiError,
+    results::{MissingItem, Paginated, ResultList},
+    search::{EntityType, SearchDetail, SearchHit},
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::formatting;
+use crate::utils::languages::LanguageQuery;
+use crate::utils::validation::{self, Validate, ValidatedJson, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct SearchArtistsQuery {
     query: String,
     limit: Option<usize>,
+    /// Fail the whole request if any result cannot be resolved
+    strict: Option<bool>,
+    /// `none` or `basic` return search hits without per-result detail calls (default `full`)
+    #[param(inline)]
+    detail: Option<SearchDetail>,
+}
+
+impl Validate for SearchArtistsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.query = validation::validate_query("query", &self.query)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct ArtistInfoQuery {
+    seokey: Option<String>,
+    /// Numeric `artist_id`, as an alternative to `seokey`
+    id: Option<String>,
+    /// Comma-separated extra data to embed; supported: `top_tracks`, and `missing` to list top
+    /// tracks that could not be resolved
+    include: Option<String>,
+    /// Number of top tracks to embed when `include=top_tracks` (default 10)
+    top_tracks_limit: Option<usize>,
+}
+
+impl ArtistInfoQuery {
+    fn includes(&self, part: &str) -> bool {
+        self.include
+            .as_deref()
+            .map(|include| include.split(',').any(|p| p.trim() == part))
+            .unwrap_or(false)
+    }
+}
+
+impl Validate for ArtistInfoQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        if let Some(include) = &self.include {
+            if let Some(unknown) = include
+                .split(',')
+                .map(str::trim)
+                .find(|part| !part.is_empty() && !["top_tracks", "missing"].contains(part))
+            {
+                return Err(ApiError::invalid_parameter(
+                    "include",
+                    &format!(
+                        "Unknown value '{}'. Supported values: top_tracks, missing",
+                        unknown
+                    ),
+                ));
+            }
+        }
+        self.top_tracks_limit = Some(validation::validate_limit(self.top_tracks_limit, 10)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlbumSort {
+    #[default]
+    Popularity,
+    ReleaseDate,
+}
+
+impl AlbumSort {
+    /// Value of Gaana's `sortBy` parameter
+    fn gaana_sort_by(self) -> &'static str {
+        match self {
+            AlbumSort::Popularity => "popularity",
+            AlbumSort::ReleaseDate => "release_date",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ArtistAlbumsQuery {
+    seokey: String,
+    /// Zero-based page number
+    page: Option<usize>,
+    /// Albums per page (default 10)
+    limit: Option<usize>,
+    #[param(inline)]
+    sort: Option<AlbumSort>,
+}
+
+impl Validate for ArtistAlbumsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.seokey = validation::validate_seokey("seokey", &self.seokey)?;
+        self.page = Some(validation::validate_page(self.page)?);
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SimilarArtistsQuery {
+    seokey: String,
+    limit: Option<usize>,
+    /// Fail the whole request if any artist cannot be resolved
+    strict: Option<bool>,
+}
+
+impl Validate for SimilarArtistsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.seokey = validation::validate_seokey("seokey", &self.seokey)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
+
+/// Deepest relationship walk allowed for `/artists/graph`
+const MAX_GRAPH_DEPTH: usize = 3;
+
+/// Most neighbours followed per artist in `/artists/graph`
+const MAX_GRAPH_NEIGHBOURS: usize = 10;
+
+/// Most artists returned by `/artists/graph`, whatever the depth
+const MAX_GRAPH_NODES: usize = 50;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ArtistGraphQuery {
     seokey: String,
+    /// How many hops to walk from the seed artist (1-3, default 2)
+    depth: Option<usize>,
+    /// Similar artists followed per node (1-10, default 5)
+    limit: Option<usize>,
+}
+
+impl Validate for ArtistGraphQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.seokey = validation::validate_seokey("seokey", &self.seokey)?;
+        match self.depth {
+            Some(depth) if depth == 0 || depth > MAX_GRAPH_DEPTH => {
+                return Err(ApiError::invalid_parameter(
+                    "depth",
+                    &format!("Must be between 1 and {}", MAX_GRAPH_DEPTH),
+                ))
+            }
+            None => self.depth = Some(2),
+            _ => {}
+        }
+        match self.limit {
+            Some(limit) if limit == 0 || limit > MAX_GRAPH_NEIGHBOURS => {
+                return Err(ApiError::invalid_parameter(
+                    "limit",
+                    &format!("Must be between 1 and {}", MAX_GRAPH_NEIGHBOURS),
+                ))
+            }
+            None => self.limit = Some(5),
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum ArtistResponse {
-    Artists(Vec<Artist>),
-    Artist(Artist),
-    Error(ApiError),
+    Artists(ResultList<Artist>),
+    Hits(ResultList<SearchHit>),
+    Artist(Box<Artist>),
 }
 
 /// Search for artists by name
 #[utoipa::path(
     get,
     path = "/artists/search",
-    params(SearchArtistsQuery),
+    params(SearchArtistsQuery, FieldsQuery, IncludeQuery),
     responses(
-        (status = 200, description = "List of artists matching the search query", body = Vec<Artist>),
-        (status = 404, description = "No artists found", body = ApiError)
+        (status = 200, description = "List of artists matching the search query (search hits with detail=none|basic); include=missing wraps them as { results, missing }", body = [Artist]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No artists found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "artists"
 )]
 pub async fn search_artists(
-    Query(params): Query<SearchArtistsQuery>,
-) -> Result<Json<ArtistResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<SearchArtistsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<ArtistResponse>, ApiError> {
+    let detail = params.detail.unwrap_or_default();
+    let limit = params.limit.unwrap_or(10);
     let api = BaseApi::new();
 
     // Use the exact same endpoint as the Python version
@@ -47,135 +218,355 @@ pub async fn search_artists(
     );
 
     eprintln!("Trying artist search endpoint: {}", search_url);
-    match api.make_request_flexible(&search_url).await {
-        Ok(response) => {
-            eprintln!("Got artist search response, extracting artist IDs...");
-
-            // Extract artist seokeys from search response like the Python version does
-            let mut artist_ids = Vec::new();
-            let limit = params.limit.unwrap_or(10);
-
-            // Parse the search response to get seokeys - same structure as songs and albums
-            if let Some(gr) = response.get("gr") {
-                if let Some(gr_array) = gr.as_array() {
-                    if let Some(first_group) = gr_array.first() {
-                        if let Some(gd) = first_group.get("gd") {
-                            if let Some(gd_array) = gd.as_array() {
-                                for (i, item) in gd_array.iter().enumerate() {
-                                    if i >= limit {
-                                        break;
-                                    }
-                                    if let Some(seo) = item.get("seo") {
-                                        if let Some(seo_str) = seo.as_str() {
-                                            artist_ids.push(seo_str.to_string());
-                                        }
-                                    }
+    let response = api.make_request_flexible(&search_url).await.map_err(|e| {
+        eprintln!("Artist search request error: {}", e);
+        e
+    })?;
+    eprintln!("Got artist search response, extracting artist IDs...");
+
+    if detail != SearchDetail::Full {
+        let hits = api.search_hits(
+            formatting::first_search_group(&response),
+            EntityType::Artist,
+            detail,
+            limit,
+            include.wants_missing(),
+        );
+        if hits.is_empty() {
+            return Err(ApiError::not_found(&params.query));
+        }
+        return Ok(Json(ArtistResponse::Hits(hits)));
+    }
+
+    // Extract artist seokeys from search response like the Python version does
+    let mut artist_ids = Vec::new();
+
+    // Parse the search response to get seokeys - same structure as songs and albums
+    if let Some(gr) = response.get("gr") {
+        if let Some(gr_array) = gr.as_array() {
+            if let Some(first_group) = gr_array.first() {
+                if let Some(gd) = first_group.get("gd") {
+                    if let Some(gd_array) = gd.as_array() {
+                        for (i, item) in gd_array.iter().enumerate() {
+                            if i >= limit {
+                                break;
+                            }
+                            if let Some(seo) = item.get("seo") {
+                                if let Some(seo_str) = seo.as_str() {
+                                    artist_ids.push(seo_str.to_string());
                                 }
                             }
                         }
                     }
                 }
             }
+        }
+    }
 
-            if artist_ids.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No artists found for the given query")),
-                ));
-            }
+    if artist_ids.is_empty() {
+        return Err(ApiError::not_found("No artists found for the given query"));
+    }
 
-            eprintln!("Found {} artist IDs: {:?}", artist_ids.len(), artist_ids);
-
-            // Now get artist info for each seokey, just like Python version
-            let mut artists = Vec::new();
-            for artist_id in artist_ids {
-                let detail_url = format!(
-                    "https://gaana.com/apiv2?type=artistDetail&seokey={}",
-                    urlencoding::encode(&artist_id)
-                );
-
-                eprintln!("Getting details for artist: {}", artist_id);
-                match api.make_request_flexible(&detail_url).await {
-                    Ok(detail_response) => {
-                        if let Some(processed_artist) =
-                            api.process_gaana_artist_response(&detail_response, false)
-                        {
-                            artists.push(processed_artist);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to get details for artist {}: {}", artist_id, e);
-                        // Continue with other artists
-                    }
-                }
-            }
+    eprintln!("Found {} artist IDs: {:?}", artist_ids.len(), artist_ids);
 
-            if artists.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No valid artist data found")),
-                ));
-            }
+    // Now get artist info for each seokey, just like Python version
+    let artists = api
+        .fetch_artists(artist_ids)
+        .await
+        .check_strict(params.strict.unwrap_or(false))?;
 
-            Ok(Json(ArtistResponse::Artists(artists)))
-        }
-        Err(e) => {
-            eprintln!("Artist search request error: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ));
-        }
+    if artists.is_empty() {
+        return Err(ApiError::not_found("No valid artist data found"));
     }
+
+    Ok(Json(ArtistResponse::Artists(
+        artists.with_missing(include.wants_missing()),
+    )))
 }
 
 /// Get detailed information about a specific artist
 #[utoipa::path(
     get,
     path = "/artists/info",
-    params(ArtistInfoQuery),
+    params(ArtistInfoQuery, FieldsQuery),
     responses(
         (status = 200, description = "Detailed information about the artist", body = Artist),
-        (status = 404, description = "Artist not found", body = ApiError)
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Artist not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "artists"
 )]
 pub async fn get_artist_info(
-    Query(params): Query<ArtistInfoQuery>,
-) -> Result<Json<ArtistResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<ArtistInfoQuery>,
+) -> Result<Json<ArtistResponse>, ApiError> {
     let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Artist,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
 
     let url = format!(
         "https://gaana.com/apiv2?type=artistDetail&seokey={}",
-        urlencoding::encode(&params.seokey)
+        urlencoding::encode(&seokey)
     );
 
     eprintln!("Getting artist info from: {}", url);
-    match api.make_request_flexible(&url).await {
-        Ok(response) => {
-            eprintln!("Parsing artist info response...");
+    let response = api.make_request_flexible(&url).await.map_err(|e| {
+        eprintln!("Artist info request failed: {}", e);
+        e
+    })?;
+    eprintln!("Parsing artist info response...");
 
-            if let Some(processed_artist) = api.process_gaana_artist_response(&response, true) {
-                return Ok(Json(ArtistResponse::Artist(processed_artist)));
-            }
+    if let Some(mut processed_artist) = api.process_gaana_artist_response(&response, None) {
+        if params.includes("top_tracks") {
+            let limit = params.top_tracks_limit.unwrap_or(10);
+            // Top tracks are extra; the artist is still worth returning without them
+            processed_artist.top_tracks = match api
+                .fetch_artist_top_tracks(&processed_artist.artist_id, limit)
+                .await
+            {
+                Ok(top_tracks) => Some(top_tracks.with_missing(params.includes("missing"))),
+                Err(e) => {
+                    eprintln!("Top tracks request failed for {}: {}", seokey, e);
+                    None
+                }
+            };
+        }
+        return Ok(Json(ArtistResponse::Artist(Box::new(processed_artist))));
+    }
+
+    eprintln!("No valid artist found in response");
+    eprintln!(
+        "Response structure: {}",
+        serde_json::to_string_pretty(&response).unwrap_or_default()
+    );
+
+    Err(ApiError::invalid_seokey(&seokey))
+}
+
+/// List an artist's albums, one page at a time
+#[utoipa::path(
+    get,
+    path = "/artists/albums",
+    params(ArtistAlbumsQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "One page of the artist's albums", body = AlbumPage),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Artist not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "artists"
+)]
+pub async fn get_artist_albums(
+    ValidatedQuery(params): ValidatedQuery<ArtistAlbumsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+    ValidatedQuery(lang): ValidatedQuery<LanguageQuery>,
+) -> Result<Json<Paginated<Album>>, ApiError> {
+    let api = BaseApi::new();
+    let page = params.page.unwrap_or(0);
+    let limit = params.limit.unwrap_or(10);
+    let sort = params.sort.unwrap_or_default();
+
+    // The album list is keyed by artist id, so resolve the seokey first
+    let artist = api.fetch_artist(&params.seokey).await?;
+
+    // Gaana sorts the list, so pages are sliced from its order as is; it does not always honour
+    // the language filter, so that is re-applied. One album past the page tells us if there are more
+    let offset = page * limit;
+    let mut albums = api
+        .fetch_artist_albums(
+            &artist.artist_id,
+            sort.gaana_sort_by(),
+            // Gaana filters by one language at a time; several are only filtered here
+            lang.languages
+                .as_ref()
+                .and_then(|l| l.single())
+                .map(|l| l.name()),
+            offset + limit + 1,
+            |album| match &lang.languages {
+                Some(languages) => album
+                    .language
+                    .as_deref()
+                    .map(|l| languages.matches(l))
+                    .unwrap_or(false),
+                None => true,
+            },
+        )
+        .await?;
+
+    let has_more = albums.results.len() > offset + limit;
+    albums.results = albums
+        .results
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect();
+
+    Ok(Json(Paginated::new(
+        albums.with_missing(include.wants_missing()),
+        page,
+        limit,
+        has_more,
+    )))
+}
+
+/// Get artists similar to a given artist
+#[utoipa::path(
+    get,
+    path = "/artists/similar",
+    params(SimilarArtistsQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "Artists related to the given artist; include=missing wraps them as { results, missing }", body = [Artist]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Artist not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "artists"
+)]
+pub async fn get_similar_artists(
+    ValidatedQuery(params): ValidatedQuery<SimilarArtistsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<ResultList<Artist>>, ApiError> {
+    let api = BaseApi::new();
+    let artist = api.fetch_artist(&params.seokey).await?;
+
+    let similar = api
+        .fetch_similar_artists(&artist.artist_id, params.limit.unwrap_or(10))
+        .await?
+        .check_strict(params.strict.unwrap_or(false))?;
+
+    if similar.is_empty() {
+        return Err(ApiError::no_results(&format!(
+            "Gaana lists no artists similar to '{}'",
+            params.seokey
+        )));
+    }
+
+    Ok(Json(similar.with_missing(include.wants_missing())))
+}
+
+/// Walk similar-artist relationships outward from a seed artist
+#[utoipa::path(
+    get,
+    path = "/artists/graph",
+    params(ArtistGraphQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "Artist nodes and similarity edges", body = ArtistGraph),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Artist not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "artists"
+)]
+pub async fn get_artist_graph(
+    ValidatedQuery(params): ValidatedQuery<ArtistGraphQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<ArtistGraph>, ApiError> {
+    let api = BaseApi::new();
+    let max_depth = params.depth.unwrap_or(2);
+    let limit = params.limit.unwrap_or(5);
+
+    let seed = api.fetch_artist(&params.seokey).await?;
 
-            eprintln!("No valid artist found in response");
-            eprintln!(
-                "Response structure: {}",
-                serde_json::to_string_pretty(&response).unwrap_or_default()
-            );
+    let mut seen = HashSet::from([seed.seokey.clone()]);
+    let mut frontier = vec![(seed.seokey.clone(), seed.artist_id.clone())];
+    let mut graph = ArtistGraph {
+        nodes: vec![ArtistNode {
+            artist: seed,
+            depth: 0,
+        }],
+        edges: Vec::new(),
+        missing: None,
+    };
+    let mut missing = Vec::new();
+    let mut edges_seen = HashSet::new();
 
-            Err((
-                StatusCode::NOT_FOUND,
-                Json(ApiError::invalid_seokey(&params.seokey)),
-            ))
+    for depth in 1..=max_depth {
+        if frontier.is_empty() || graph.nodes.len() >= MAX_GRAPH_NODES {
+            break;
         }
-        Err(e) => {
-            eprintln!("Artist info request failed: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ))
+
+        // Expand the whole level at once, a few artists at a time
+        let api = &api;
+        let expanded: Vec<_> = stream::iter(frontier)
+            .map(|(seokey, artist_id)| async move {
+                let similar = api.fetch_similar_artists(&artist_id, limit).await;
+                (seokey, similar)
+            })
+            .buffered(4)
+            .collect()
+            .await;
+
+        let mut next = Vec::new();
+        for (source, similar) in expanded {
+            let similar = match similar {
+                Ok(similar) => similar,
+                Err(e) => {
+                    missing.push(MissingItem::new(&source, &e));
+                    continue;
+                }
+            };
+            missing.extend(similar.missing);
+
+            for artist in similar.results {
+                if !seen.contains(&artist.seokey) && graph.nodes.len() < MAX_GRAPH_NODES {
+                    seen.insert(artist.seokey.clone());
+                    next.push((artist.seokey.clone(), artist.artist_id.clone()));
+                    graph.nodes.push(ArtistNode {
+                        artist: artist.clone(),
+                        depth,
+                    });
+                }
+                // Only keep edges between artists that made it into the graph
+                let edge = ArtistEdge {
+                    source: source.clone(),
+                    target: artist.seokey,
+                };
+                if seen.contains(&edge.target) && edges_seen.insert(edge.clone()) {
+                    graph.edges.push(edge);
+                }
+            }
         }
+        frontier = next;
     }
+
+    graph.missing = include.wants_missing().then_some(missing);
+    Ok(Json(graph))
+}
+
+/// Look up many artists at once by seokey or id
+#[utoipa::path(
+    post,
+    path = "/artists/batch",
+    params(FieldsQuery),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Artists keyed by the requested seokey or id, with failures under `missing`", body = ArtistBatch),
+        (status = 400, description = "Invalid request body", body = ApiError)
+    ),
+    tag = "artists"
+)]
+pub async fn batch_artists(
+    ValidatedJson(request): ValidatedJson<BatchRequest>,
+) -> Json<BatchResponse<Artist>> {
+    let api = BaseApi::new();
+    let api = &api;
+    Json(
+        api.fetch_batch(EntityType::Artist, request, |seokey| async move {
+            api.fetch_artist_cached(&seokey).await
+        })
+        .await,
+    )
 }
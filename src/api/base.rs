@@ -1,8 +1,71 @@
-use crate::models::{album::*, artist::*, images::Images, playlist::*, song::*};
-use crate::utils::{encryption, formatting};
-use anyhow::Result;
-use reqwest::Client;
+use crate::models::{
+    album::*,
+    artist::*,
+    batch::{BatchRequest, BatchResponse},
+    error::{ApiError, ErrorCode},
+    images::Images,
+    playlist::*,
+    results::{MissingItem, Paginated, ResultList},
+    search::{EntityType, SearchDetail, SearchHit},
+    song::*,
+};
+use crate::utils::{
+    cache::TtlCache, encryption, formatting, id_index, lyrics, recommend, validation,
+};
+use futures::{future::join_all, stream, Future, StreamExt};
+use reqwest::{redirect, Client, RequestBuilder};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// How long to wait for Gaana before giving up with `UPSTREAM_TIMEOUT`
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How many detail requests are in flight at once during a fan-out
+const FAN_OUT_CONCURRENCY: usize = 8;
+
+/// Artists of a seed song whose top tracks feed heuristic recommendations
+const CANDIDATE_ARTISTS: usize = 2;
+
+/// Most `artistAlbumList` pages read to fill one page of an artist's albums
+const MAX_ARTIST_ALBUM_PAGES: usize = 20;
+
+/// Most search pages read to fill one page of a search-backed listing
+const MAX_SEARCH_PAGES: usize = 20;
+
+/// How long batch lookups reuse a fetched entity
+const ENTITY_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Entities of each type kept for batch lookups
+const ENTITY_CACHE_CAPACITY: usize = 10_000;
+
+/// Most redirects followed when expanding a share link
+const MAX_SHARE_LINK_REDIRECTS: usize = 5;
+
+/// Client for share links: follows redirects only while they stay on Gaana's hosts
+static SHARE_LINK_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(UPSTREAM_TIMEOUT)
+        .redirect(redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() > MAX_SHARE_LINK_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if formatting::is_gaana_url(attempt.url()) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()
+        .unwrap_or_default()
+});
+
+static SONG_CACHE: LazyLock<TtlCache<Song>> =
+    LazyLock::new(|| TtlCache::new(ENTITY_CACHE_TTL, ENTITY_CACHE_CAPACITY));
+static ALBUM_CACHE: LazyLock<TtlCache<Album>> =
+    LazyLock::new(|| TtlCache::new(ENTITY_CACHE_TTL, ENTITY_CACHE_CAPACITY));
+static ARTIST_CACHE: LazyLock<TtlCache<Artist>> =
+    LazyLock::new(|| TtlCache::new(ENTITY_CACHE_TTL, ENTITY_CACHE_CAPACITY));
 
 pub struct BaseApi {
     client: Client,
@@ -11,48 +74,42 @@ pub struct BaseApi {
 impl BaseApi {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: Client::builder()
+                .timeout(UPSTREAM_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
         }
     }
-    pub async fn make_request(&self, url: &str) -> Result<Value> {
-        let response = self
+    pub async fn make_request(&self, url: &str) -> Result<Value, ApiError> {
+        let request = self
             .client
             .post(url)
             .header(
                 "User-Agent",
                 "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36",
             )
-            .header("Content-Length", "0")
-            .send()
-            .await?;
+            .header("Content-Length", "0");
 
-        // Check response status
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "HTTP request failed with status: {}",
-                response.status()
-            ));
-        }
-
-        // Get response text first for better error diagnostics
-        let response_text = response.text().await?;
+        let response_text = self.send(request).await?;
 
         // Log the first few characters for debugging
         if response_text.len() > 200 {
-            eprintln!("Response preview: {}", &response_text[..200]);
+            eprintln!(
+                "Response preview: {}",
+                response_text.chars().take(200).collect::<String>()
+            );
         } else {
             eprintln!("Full response: {}", response_text);
         }
 
         // Try to parse as JSON
-        let json_value: Value = serde_json::from_str(&response_text)
-            .map_err(|e| anyhow::anyhow!("error decoding response body: {}", e))?;
-
-        Ok(json_value)
+        serde_json::from_str(&response_text).map_err(|e| {
+            ApiError::upstream_schema_changed(&format!("error decoding response body: {}", e))
+        })
     }
     /// More flexible method to handle different possible response structures
-    pub async fn make_request_flexible(&self, url: &str) -> Result<Value> {
-        let response = self
+    pub async fn make_request_flexible(&self, url: &str) -> Result<Value, ApiError> {
+        let request = self
             .client
             .post(url)
             .header(
@@ -61,46 +118,640 @@ impl BaseApi {
             )
             .header("Accept", "application/json, text/plain, */*")
             .header("Content-Length", "0")
-            .header("Referer", "https://gaana.com/")
+            .header("Referer", "https://gaana.com/");
+
+        let response_text = self.send(request).await?;
+        Self::parse_json(&response_text)
+    }
+
+    /// Follow a share link's redirects and return the page URL it lands on; hops off Gaana's
+    /// hosts are not followed
+    pub async fn expand_share_link(&self, url: &str) -> Result<String, ApiError> {
+        let response = SHARE_LINK_CLIENT
+            .get(url)
+            .header(
+                "User-Agent",
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36",
+            )
             .send()
             .await?;
+        Ok(response.url().to_string())
+    }
+
+    /// Request that passes the language through Gaana's `__ul` cookie, as the trending feed expects
+    pub async fn make_request_with_language(
+        &self,
+        url: &str,
+        language: &str,
+    ) -> Result<Value, ApiError> {
+        let request = self
+            .client
+            .post(url)
+            .header("Cookie", format!("__ul={}", language))
+            .header("Content-Length", "0")
+            .body("");
+
+        let response_text = self.send(request).await?;
+        Self::parse_json(&response_text)
+    }
+
+    /// Send a request and return the body, mapping transport and status failures to API errors
+    async fn send(&self, request: RequestBuilder) -> Result<String, ApiError> {
+        let response = request.send().await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "HTTP request failed with status: {}",
-                response.status()
-            ));
+            return Err(ApiError::from_upstream_status(response.status()));
         }
 
-        let response_text = response.text().await?;
+        Ok(response.text().await?)
+    }
 
+    fn parse_json(response_text: &str) -> Result<Value, ApiError> {
         if response_text.trim().is_empty() {
-            return Err(anyhow::anyhow!("Empty response from server"));
+            return Err(ApiError::upstream_schema_changed(
+                "Empty response from server",
+            ));
         }
 
         let trimmed = response_text.trim();
         if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
             if trimmed.contains("<html") || trimmed.contains("<!DOCTYPE") {
-                return Err(anyhow::anyhow!(
-                    "Server returned HTML instead of JSON. Possible API endpoint change."
+                return Err(ApiError::upstream_schema_changed(
+                    "Server returned HTML instead of JSON. Possible API endpoint change.",
                 ));
             }
-            return Err(anyhow::anyhow!(
+            return Err(ApiError::upstream_schema_changed(&format!(
                 "Response is not valid JSON format: {}",
-                &trimmed[..std::cmp::min(100, trimmed.len())]
-            ));
+                trimmed.chars().take(100).collect::<String>()
+            )));
         }
 
-        match serde_json::from_str::<Value>(&response_text) {
-            Ok(json_value) => Ok(json_value),
-            Err(e) => Err(anyhow::anyhow!(
+        serde_json::from_str::<Value>(response_text).map_err(|e| {
+            ApiError::upstream_schema_changed(&format!(
                 "Failed to parse JSON response: {}. Response text: {}",
-                e,
-                response_text
+                e, response_text
+            ))
+        })
+    }
+
+    /// Seokey to look up for an info request given either `seokey` or `id`
+    pub async fn seokey_or_id(
+        &self,
+        entity_type: EntityType,
+        seokey: Option<&str>,
+        id: Option<&str>,
+    ) -> Result<String, ApiError> {
+        match (seokey, id) {
+            (Some(seokey), _) => Ok(seokey.to_string()),
+            (None, Some(id)) => self.resolve_id(entity_type, id).await,
+            (None, None) => Err(ApiError::invalid_parameter(
+                "seokey",
+                "One of seokey or id is required",
             )),
         }
     }
 
+    /// Find the seokey for a numeric id, from entities seen before or Gaana's id-keyed detail calls
+    pub async fn resolve_id(&self, entity_type: EntityType, id: &str) -> Result<String, ApiError> {
+        if let Some(seokey) = id_index::lookup(entity_type, id) {
+            return Ok(seokey);
+        }
+        let (detail_type, id_param) = match entity_type {
+            EntityType::Song => ("songDetail", "track_id"),
+            EntityType::Album => ("albumDetail", "album_id"),
+            EntityType::Artist => ("artistDetail", "artist_id"),
+            EntityType::Playlist => ("playlistDetail", "playlist_id"),
+        };
+        let url = format!(
+            "https://gaana.com/apiv2?type={}&{}={}",
+            detail_type,
+            id_param,
+            urlencoding::encode(id)
+        );
+        let response = self.make_request_flexible(&url).await?;
+        let entity = match entity_type {
+            EntityType::Song => response.get("tracks").and_then(|t| t.get(0)),
+            EntityType::Album => response.get("album"),
+            EntityType::Artist => response.get("artist").and_then(|a| a.get(0)),
+            EntityType::Playlist => response.get("playlist"),
+        };
+        let seokey = entity
+            .and_then(|e| e.get("seokey"))
+            .and_then(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| ApiError::invalid_id(id))?;
+        id_index::remember(entity_type, id, seokey);
+        Ok(seokey.to_string())
+    }
+
+    /// Fetch a single song by seokey via `songDetail`
+    pub async fn fetch_song(&self, seokey: &str) -> Result<Song, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?type=songDetail&seokey={}",
+            urlencoding::encode(seokey)
+        );
+        let response = self.make_request_flexible(&url).await?;
+        response
+            .get("tracks")
+            .and_then(|t| t.as_array())
+            .and_then(|tracks| tracks.first())
+            .and_then(|track| serde_json::from_value::<GaanaTrack>(track.clone()).ok())
+            .and_then(|track| self.process_gaana_track(&track))
+            .ok_or_else(|| ApiError::invalid_seokey(seokey))
+    }
+
+    /// `fetch_song`, reusing songs fetched in the last few minutes
+    pub async fn fetch_song_cached(&self, seokey: &str) -> Result<Song, ApiError> {
+        if let Some(song) = SONG_CACHE.get(seokey) {
+            return Ok(song);
+        }
+        let song = self.fetch_song(seokey).await?;
+        SONG_CACHE.insert(seokey.to_string(), song.clone());
+        Ok(song)
+    }
+
+    /// Fetch a single album by seokey via `albumDetail`
+    pub async fn fetch_album(&self, seokey: &str, include_tracks: bool) -> Result<Album, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?type=albumDetail&seokey={}",
+            urlencoding::encode(seokey)
+        );
+        let response = self.make_request_flexible(&url).await?;
+        self.process_gaana_album_response(&response, include_tracks)
+            .ok_or_else(|| ApiError::invalid_seokey(seokey))
+    }
+
+    /// `fetch_album` without tracks, reusing albums fetched in the last few minutes
+    pub async fn fetch_album_cached(&self, seokey: &str) -> Result<Album, ApiError> {
+        if let Some(album) = ALBUM_CACHE.get(seokey) {
+            return Ok(album);
+        }
+        let album = self.fetch_album(seokey, false).await?;
+        ALBUM_CACHE.insert(seokey.to_string(), album.clone());
+        Ok(album)
+    }
+
+    /// Fetch a single artist by seokey via `artistDetail`
+    pub async fn fetch_artist(&self, seokey: &str) -> Result<Artist, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?type=artistDetail&seokey={}",
+            urlencoding::encode(seokey)
+        );
+        let response = self.make_request_flexible(&url).await?;
+        self.process_gaana_artist_response(&response, None)
+            .ok_or_else(|| ApiError::invalid_seokey(seokey))
+    }
+
+    /// `fetch_artist`, reusing artists fetched in the last few minutes
+    pub async fn fetch_artist_cached(&self, seokey: &str) -> Result<Artist, ApiError> {
+        if let Some(artist) = ARTIST_CACHE.get(seokey) {
+            return Ok(artist);
+        }
+        let artist = self.fetch_artist(seokey).await?;
+        ARTIST_CACHE.insert(seokey.to_string(), artist.clone());
+        Ok(artist)
+    }
+
+    /// Fetch a single playlist's metadata by seokey via `playlistDetail`
+    pub async fn fetch_playlist(&self, seokey: &str) -> Result<Playlist, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?type=playlistDetail&seokey={}",
+            urlencoding::encode(seokey)
+        );
+        let response = self.make_request_flexible(&url).await?;
+        let mut playlist = response
+            .get("playlist")
+            .and_then(|p| serde_json::from_value::<GaanaPlaylist>(p.clone()).ok())
+            .and_then(|p| self.process_gaana_playlist(&p, None))
+            .ok_or_else(|| ApiError::invalid_seokey(seokey))?;
+        if playlist.total_tracks.is_none() {
+            playlist.total_tracks = response
+                .get("tracks")
+                .and_then(|t| t.as_array())
+                .map(|tracks| tracks.len() as i32);
+        }
+        Ok(playlist)
+    }
+
+    /// Fetch an artist's most popular tracks via `artistTrackList`
+    pub async fn fetch_artist_top_tracks(
+        &self,
+        artist_id: &str,
+        limit: usize,
+    ) -> Result<ResultList<Song>, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?id={}&language=&order=0&page=0&sortBy=popularity&type=artistTrackList",
+            urlencoding::encode(artist_id)
+        );
+        let response = self.make_request_flexible(&url).await?;
+
+        // Full track objects can be normalized directly; bare entities need a detail call each
+        if let Some(tracks) = response.get("tracks").and_then(|t| t.as_array()) {
+            let results = tracks
+                .iter()
+                .filter_map(|track| serde_json::from_value::<GaanaTrack>(track.clone()).ok())
+                .filter_map(|track| self.process_gaana_track(&track))
+                .take(limit)
+                .collect();
+            return Ok(ResultList::new(results));
+        }
+
+        let seokeys = formatting::extract_seokeys(&response, "entities")
+            .into_iter()
+            .take(limit)
+            .collect();
+        Ok(self.fetch_songs(seokeys).await)
+    }
+
+    /// Collect an artist's albums from the start of Gaana's `artistAlbumList`, one upstream
+    /// page at a time, until `keep` has accepted `want` of them or the list runs out.
+    /// Gaana decides its own page size, so our pages are sliced from this list by the caller.
+    pub async fn fetch_artist_albums(
+        &self,
+        artist_id: &str,
+        sort_by: &str,
+        language: Option<&str>,
+        want: usize,
+        keep: impl Fn(&Album) -> bool,
+    ) -> Result<ResultList<Album>, ApiError> {
+        let mut list = ResultList::new(Vec::new());
+        let mut seen = HashSet::new();
+        for gaana_page in 0..MAX_ARTIST_ALBUM_PAGES {
+            let page = match self
+                .fetch_artist_album_page(artist_id, gaana_page, sort_by, language)
+                .await
+            {
+                Ok(page) => page,
+                Err(e) if gaana_page > 0 => {
+                    eprintln!("Artist album page {} failed: {}", gaana_page, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // An empty page, or one that only repeats earlier albums, is the end of the list
+            let before = seen.len();
+            for album in page.results {
+                if seen.insert(album.seokey.clone()) && keep(&album) {
+                    list.results.push(album);
+                }
+            }
+            for item in page.missing {
+                if seen.insert(item.seokey.clone()) {
+                    list.missing.push(item);
+                }
+            }
+            if seen.len() == before || list.results.len() >= want {
+                break;
+            }
+        }
+        Ok(list)
+    }
+
+    /// Fetch one of Gaana's `artistAlbumList` pages, resolving bare entities to albums
+    async fn fetch_artist_album_page(
+        &self,
+        artist_id: &str,
+        gaana_page: usize,
+        sort_by: &str,
+        language: Option<&str>,
+    ) -> Result<ResultList<Album>, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?id={}&language={}&order=0&page={}&sortBy={}&type=artistAlbumList",
+            urlencoding::encode(artist_id),
+            urlencoding::encode(language.unwrap_or("")),
+            gaana_page,
+            sort_by
+        );
+        let response = self.make_request_flexible(&url).await?;
+
+        // Full album objects can be normalized directly; bare entities need a detail call each
+        if let Some(albums) = response.get("album").and_then(|a| a.as_array()) {
+            let results = albums
+                .iter()
+                .filter_map(|album| serde_json::from_value::<GaanaAlbum>(album.clone()).ok())
+                .filter_map(|album| self.process_gaana_album(&album, None))
+                .collect();
+            return Ok(ResultList::new(results));
+        }
+
+        let seokeys = formatting::extract_seokeys(&response, "entities");
+        Ok(self.fetch_albums(seokeys).await)
+    }
+
+    /// Fetch artists Gaana considers similar to the given one
+    pub async fn fetch_similar_artists(
+        &self,
+        artist_id: &str,
+        limit: usize,
+    ) -> Result<ResultList<Artist>, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?id={}&type=similarArtist",
+            urlencoding::encode(artist_id)
+        );
+        let response = self.make_request_flexible(&url).await?;
+
+        // Full artist objects can be normalized directly; bare entities need a detail call each
+        if let Some(artists) = response.get("artist").and_then(|a| a.as_array()) {
+            let results = artists
+                .iter()
+                .filter_map(|artist| self.process_gaana_artist_value(artist, None))
+                .take(limit)
+                .collect();
+            return Ok(ResultList::new(results));
+        }
+
+        let seokeys = formatting::extract_seokeys(&response, "entities")
+            .into_iter()
+            .take(limit)
+            .collect();
+        Ok(self.fetch_artists(seokeys).await)
+    }
+
+    /// Fetch tracks Gaana considers similar to the given one
+    pub async fn fetch_similar_songs(
+        &self,
+        track_id: &str,
+        limit: usize,
+    ) -> Result<ResultList<Song>, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?id={}&type=similarTrack",
+            urlencoding::encode(track_id)
+        );
+        let response = self.make_request_flexible(&url).await?;
+
+        // Full track objects can be normalized directly; bare entities need a detail call each
+        if let Some(tracks) = response.get("tracks").and_then(|t| t.as_array()) {
+            let results = tracks
+                .iter()
+                .filter_map(|track| serde_json::from_value::<GaanaTrack>(track.clone()).ok())
+                .filter_map(|track| self.process_gaana_track(&track))
+                .filter(|song| song.track_id != track_id)
+                .take(limit)
+                .collect();
+            return Ok(ResultList::new(results));
+        }
+
+        let seokeys = formatting::extract_seokeys(&response, "entities")
+            .into_iter()
+            .take(limit)
+            .collect();
+        Ok(self.fetch_songs(seokeys).await)
+    }
+
+    /// Up to `want` seokeys from the start of a `secType` search (`track`, `playlist`, ...),
+    /// reading Gaana's pages until there are enough or the results run out.
+    /// Gaana decides its own page size, so our pages are sliced from this list by the caller.
+    pub async fn search_seokeys(
+        &self,
+        keyword: &str,
+        sec_type: &str,
+        want: usize,
+    ) -> Result<Vec<String>, ApiError> {
+        let mut seokeys: Vec<String> = Vec::new();
+        for gaana_page in 0..MAX_SEARCH_PAGES {
+            let url = format!(
+                "https://gaana.com/apiv2?country=IN&page={}&secType={}&type=search&keyword={}",
+                gaana_page,
+                sec_type,
+                urlencoding::encode(keyword)
+            );
+            let response = match self.make_request_flexible(&url).await {
+                Ok(response) => response,
+                Err(e) if gaana_page > 0 => {
+                    eprintln!("Search page {} for '{}' failed: {}", gaana_page, keyword, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // An empty page, or one that only repeats earlier results, is the end of the list
+            let before = seokeys.len();
+            for item in formatting::search_group_items(
+                formatting::first_search_group(&response),
+                usize::MAX,
+            ) {
+                if let Some(seo) = item.get("seo").and_then(|s| s.as_str()) {
+                    if !seokeys.iter().any(|s| s == seo) {
+                        seokeys.push(seo.to_string());
+                    }
+                }
+            }
+            if seokeys.len() == before || seokeys.len() >= want {
+                break;
+            }
+        }
+        seokeys.truncate(want);
+        Ok(seokeys)
+    }
+
+    /// `detail=none|basic` hits from up to `limit` entries of a search group, without detail
+    /// calls; `include_missing` is passed through from `include=missing`
+    pub fn search_hits(
+        &self,
+        group: Option<&Value>,
+        entity_type: EntityType,
+        detail: SearchDetail,
+        limit: usize,
+        include_missing: bool,
+    ) -> ResultList<SearchHit> {
+        ResultList::new(
+            formatting::search_group_items(group, usize::MAX)
+                .into_iter()
+                .filter_map(|item| SearchHit::from_search_item(item, entity_type, detail))
+                .take(limit)
+                .collect(),
+        )
+        .with_missing(include_missing)
+    }
+
+    /// Raw lyrics text of a track via `songLyrics`; `None` when Gaana has none
+    pub async fn fetch_lyrics(&self, track_id: &str) -> Result<Option<String>, ApiError> {
+        let url = format!(
+            "https://gaana.com/apiv2?type=songLyrics&track_id={}",
+            urlencoding::encode(track_id)
+        );
+        match self.make_request_flexible(&url).await {
+            Ok(response) => Ok(lyrics::extract_lyrics_text(&response)),
+            Err(e) if e.code == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Songs near `seed` for heuristic recommendations: its album's tracks and the top
+    /// tracks of its first artists. Sources that fail are skipped.
+    pub async fn fetch_song_candidates(&self, seed: &Song, per_source: usize) -> Vec<Song> {
+        let album = async {
+            match &seed.album_seokey {
+                Some(seokey) => self
+                    .fetch_album(seokey, true)
+                    .await
+                    .map(|album| album.tracks.unwrap_or_default()),
+                None => Ok(Vec::new()),
+            }
+        };
+        let artist_ids: Vec<String> = recommend::split_list(&seed.artist_ids)
+            .into_iter()
+            .filter(|id| id != "0")
+            .take(CANDIDATE_ARTISTS)
+            .collect();
+        let artists = join_all(
+            artist_ids
+                .iter()
+                .map(|id| self.fetch_artist_top_tracks(id, per_source)),
+        );
+        let (album, artists) = tokio::join!(album, artists);
+
+        let mut candidates = Vec::new();
+        for source in std::iter::once(album).chain(
+            artists
+                .into_iter()
+                .map(|tracks| tracks.map(|list| list.results)),
+        ) {
+            match source {
+                Ok(songs) => candidates.extend(songs),
+                Err(e) => eprintln!("Skipping recommendation source: {}", e.message),
+            }
+        }
+        candidates
+    }
+
+    pub async fn fetch_songs(&self, seokeys: Vec<String>) -> ResultList<Song> {
+        self.fan_out(
+            seokeys,
+            |seokey| async move { self.fetch_song(&seokey).await },
+        )
+        .await
+    }
+
+    pub async fn fetch_albums(&self, seokeys: Vec<String>) -> ResultList<Album> {
+        self.fan_out(seokeys, |seokey| async move {
+            self.fetch_album(&seokey, false).await
+        })
+        .await
+    }
+
+    pub async fn fetch_artists(&self, seokeys: Vec<String>) -> ResultList<Artist> {
+        self.fan_out(
+            seokeys,
+            |seokey| async move { self.fetch_artist(&seokey).await },
+        )
+        .await
+    }
+
+    pub async fn fetch_playlists(&self, seokeys: Vec<String>) -> ResultList<Playlist> {
+        self.fan_out(seokeys, |seokey| async move {
+            self.fetch_playlist(&seokey).await
+        })
+        .await
+    }
+
+    /// Resolve many seokeys concurrently, keeping input order and recording failures
+    pub async fn fan_out<T, F, Fut>(&self, seokeys: Vec<String>, fetch: F) -> ResultList<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let outcomes = self.fan_out_keyed(seokeys, fetch).await;
+
+        let mut list = ResultList::new(Vec::new());
+        for (seokey, outcome) in outcomes {
+            match outcome {
+                Ok(item) => list.results.push(item),
+                Err(e) => {
+                    eprintln!("Failed to get details for {}: {}", seokey, e);
+                    list.missing.push(MissingItem::new(&seokey, &e));
+                }
+            }
+        }
+        list
+    }
+
+    /// Run `fetch` for every key with bounded concurrency, pairing each outcome with its key
+    pub async fn fan_out_keyed<T, F, Fut>(
+        &self,
+        keys: Vec<String>,
+        fetch: F,
+    ) -> Vec<(String, Result<T, ApiError>)>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        stream::iter(keys)
+            .map(|key| {
+                let fetched = fetch(key.clone());
+                async move { (key, fetched.await) }
+            })
+            .buffered(FAN_OUT_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Resolve a batch of seokeys and ids, keyed by input; invalid inputs fail individually
+    pub async fn fetch_batch<T, F, Fut>(
+        &self,
+        entity_type: EntityType,
+        request: BatchRequest,
+        fetch: F,
+    ) -> BatchResponse<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let mut missing = Vec::new();
+        let mut keys: Vec<(String, bool)> = Vec::new();
+        let inputs = request
+            .seokeys
+            .into_iter()
+            .map(|seokey| (seokey, false))
+            .chain(request.ids.into_iter().map(|id| (id, true)));
+        for (input, is_id) in inputs {
+            let checked = if is_id {
+                validation::validate_id("ids", &input)
+            } else {
+                validation::validate_seokey("seokeys", &input)
+            };
+            match checked {
+                Ok(key) if !keys.iter().any(|(k, _)| *k == key) => keys.push((key, is_id)),
+                Ok(_) => {}
+                Err(e) => missing.push(MissingItem::new(&input, &e)),
+            }
+        }
+
+        let ids: Vec<String> = keys
+            .iter()
+            .filter(|(_, is_id)| *is_id)
+            .map(|(k, _)| k.clone())
+            .collect();
+        let fetch = &fetch;
+        let ids = &ids;
+        let outcomes = self
+            .fan_out_keyed(
+                keys.into_iter().map(|(k, _)| k).collect(),
+                |key| async move {
+                    let seokey = if ids.contains(&key) {
+                        self.resolve_id(entity_type, &key).await?
+                    } else {
+                        key
+                    };
+                    fetch(seokey).await
+                },
+            )
+            .await;
+
+        let mut results = BTreeMap::new();
+        for (key, outcome) in outcomes {
+            match outcome {
+                Ok(item) => {
+                    results.insert(key, item);
+                }
+                Err(e) => missing.push(MissingItem::new(&key, &e)),
+            }
+        }
+        BatchResponse { results, missing }
+    }
+
+    #[allow(dead_code)]
     pub fn process_song_response(&self, response: &Value) -> Vec<Song> {
         let mut songs = Vec::new();
         if let Some(tracks) = response.get("tracks").and_then(|t| t.as_array()) {
@@ -141,31 +792,23 @@ impl BaseApi {
         };
 
         // Create images
-        let images = if let Some(artwork) = &track.artwork {
-            Some(Images::new(
+        let images = track.artwork.as_ref().map(|artwork| {
+            Images::new(
                 track.artwork_large.clone(),
                 Some(artwork.clone()),
                 track.artwork_web.clone(),
-            ))
-        } else {
-            None
-        };
+            )
+        });
 
         // Process stream URLs if available
-        let stream_urls = if let Some(urls) = &track.urls {
-            if let Some(medium) = &urls.medium {
-                if let Some(encrypted_url) = &medium.message {
-                    Some(encryption::decrypt_stream_url(encrypted_url))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        let stream_urls = track
+            .urls
+            .as_ref()
+            .and_then(|urls| urls.medium.as_ref())
+            .and_then(|medium| medium.message.as_ref())
+            .map(|encrypted_url| encryption::decrypt_stream_url(encrypted_url));
 
+        id_index::remember(EntityType::Song, &track_id, &seokey);
         Some(Song {
             seokey: seokey.clone(),
             album_seokey: track.album_seokey.clone(),
@@ -181,6 +824,7 @@ impl BaseApi {
             popularity: track.popularity.clone(),
             genres: formatting::process_genres(&track.genre),
             is_explicit: formatting::extract_int(&track.explicit_content),
+            has_lyrics: track.lyrics_url.as_ref().map(|url| !url.trim().is_empty()),
             language: track.language.clone(),
             label: track.label.clone(),
             release_date: track.release_date.clone(),
@@ -210,15 +854,14 @@ impl BaseApi {
             formatting::process_album_artists(&album.artist);
 
         // Create images
-        let images = if let Some(artwork) = &album.artwork {
-            Some(Images::new(
+        let images = album.artwork.as_ref().map(|artwork| {
+            Images::new(
                 album.artwork_large.clone(),
                 Some(artwork.clone()),
                 album.artwork_web.clone(),
-            ))
-        } else {
-            None
-        };
+            )
+        });
+        id_index::remember(EntityType::Album, &album_id, &seokey);
         Some(Album {
             seokey: seokey.clone(),
             album_id,
@@ -241,25 +884,24 @@ impl BaseApi {
         })
     }
 
+    #[allow(dead_code)]
     pub fn process_gaana_artist(
         &self,
         artist: &GaanaArtist,
-        top_tracks: Option<Vec<Song>>,
+        top_tracks: Option<ResultList<Song>>,
     ) -> Option<Artist> {
         let seokey = artist.seokey.as_ref()?.clone();
         let artist_id = formatting::extract_id(&artist.artist_id);
         let name = artist.name.as_ref()?.clone();
 
         // Create images
-        let images = if let Some(artwork) = &artist.artwork {
-            Some(Images::new(
+        let images = artist.artwork.as_ref().map(|artwork| {
+            Images::new(
                 artist.artwork_large.clone(),
                 Some(artwork.clone()),
                 artist.artwork_web.clone(),
-            ))
-        } else {
-            None
-        };
+            )
+        });
         Some(Artist {
             seokey: seokey.clone(),
             artist_id,
@@ -279,22 +921,21 @@ impl BaseApi {
     pub fn process_gaana_playlist(
         &self,
         playlist: &GaanaPlaylist,
-        tracks: Option<Vec<Song>>,
+        tracks: Option<Paginated<Song>>,
     ) -> Option<Playlist> {
         let seokey = playlist.seokey.as_ref()?.clone();
         let playlist_id = formatting::extract_id(&playlist.playlist_id);
         let title = playlist.title.as_ref()?.clone();
 
         // Create images
-        let images = if let Some(artwork) = &playlist.artwork {
-            Some(Images::new(
+        let images = playlist.artwork.as_ref().map(|artwork| {
+            Images::new(
                 playlist.artwork_large.clone(),
                 Some(artwork.clone()),
                 playlist.artwork_web.clone(),
-            ))
-        } else {
-            None
-        };
+            )
+        });
+        id_index::remember(EntityType::Playlist, &playlist_id, &seokey);
         Some(Playlist {
             seokey: seokey.clone(),
             playlist_id,
@@ -308,7 +949,9 @@ impl BaseApi {
                 .clone()
                 .unwrap_or_else(|| format!("https://gaana.com/playlist/{}", seokey)),
             images,
-            tracks_url: format!("/playlists/info?seokey={}", seokey)
+            tracks_url: format!("/playlists/info?seokey={}&tracks_only=true", seokey),
+            total_tracks: formatting::extract_int(&playlist.total_tracks),
+            tracks,
         })
     }
     /// Process album response from the API just like the Python version
@@ -350,15 +993,16 @@ impl BaseApi {
             };
 
         // Create images from artwork
-        let images = if let Some(artwork) = album_data.get("artwork").and_then(|v| v.as_str()) {
-            Some(Images::new(
-                Some(artwork.replace("size_s.jpg", "size_l.jpg")),
-                Some(artwork.to_string()),
-                Some(artwork.replace("size_s.jpg", "size_m.jpg")),
-            ))
-        } else {
-            None
-        };
+        let images = album_data
+            .get("artwork")
+            .and_then(|v| v.as_str())
+            .map(|artwork| {
+                Images::new(
+                    Some(artwork.replace("size_s.jpg", "size_l.jpg")),
+                    Some(artwork.to_string()),
+                    Some(artwork.replace("size_s.jpg", "size_m.jpg")),
+                )
+            });
 
         // Process tracks if requested
         let tracks = if include_tracks {
@@ -387,6 +1031,7 @@ impl BaseApi {
         } else {
             None
         };
+        id_index::remember(EntityType::Album, &album_id, &seokey);
         Some(Album {
             seokey: seokey.clone(),
             album_id,
@@ -421,35 +1066,37 @@ impl BaseApi {
     pub fn process_gaana_artist_response(
         &self,
         response: &Value,
-        include_top_tracks: bool,
+        top_tracks: Option<ResultList<Song>>,
     ) -> Option<Artist> {
         // The Python code expects: result['artist'][0]
         let artist_array = response.get("artist")?.as_array()?;
         let artist_data = artist_array.first()?;
+        self.process_gaana_artist_value(artist_data, top_tracks)
+    }
 
+    /// Normalize one raw artist object as found in `artistDetail` and related lists
+    pub fn process_gaana_artist_value(
+        &self,
+        artist_data: &Value,
+        top_tracks: Option<ResultList<Song>>,
+    ) -> Option<Artist> {
         let seokey = artist_data.get("seokey")?.as_str()?.to_string();
         let artist_id = formatting::extract_id(&artist_data.get("artist_id").cloned());
         let name = artist_data.get("name")?.as_str()?.to_string();
 
         // Create images from artwork
-        let images = if let Some(artwork) = artist_data.get("atw").and_then(|v| v.as_str()) {
-            Some(Images::new(
-                Some(artwork.replace("size_m", "size_l")),
-                Some(artwork.to_string()),
-                Some(artwork.replace("size_m", "size_s")),
-            ))
-        } else {
-            None
-        };
-        // Process top tracks if requested
-        let top_tracks = if include_top_tracks {
-            // For now, we don't include top tracks in the simple response
-            // This would require an additional async call to get top tracks
-            None
-        } else {
-            None
-        };
+        let images = artist_data
+            .get("atw")
+            .and_then(|v| v.as_str())
+            .map(|artwork| {
+                Images::new(
+                    Some(artwork.replace("size_m", "size_l")),
+                    Some(artwork.to_string()),
+                    Some(artwork.replace("size_m", "size_s")),
+                )
+            });
 
+        id_index::remember(EntityType::Artist, &artist_id, &seokey);
         Some(Artist {
             seokey: seokey.clone(),
             artist_id,
@@ -1,91 +1,114 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::{IntoResponse, Json};
+use futures::future::join_all;
 use serde::Deserialize;
-use utoipa::IntoParams;
 use urlencoding::encode;
+use utoipa::IntoParams;
 
 use crate::api::base::BaseApi;
-use crate::models::{error::ApiError, playlist::*, images::Images};
+use crate::models::{error::ApiError, images::Images, language::Language, playlist::*};
+use crate::utils::fields::FieldsQuery;
 use crate::utils::formatting;
+use crate::utils::languages::{LanguageQuery, PreferredLanguages};
+use crate::utils::validation::{self, Validate, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct ChartsQuery {
-    #[serde(rename = "lang")]
-    language: Option<String>,
     limit: Option<usize>,
 }
 
+impl Validate for ChartsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
+
 /// Get current top charts (list of popular playlists)
 #[utoipa::path(
     get,
     path = "/charts",
-    params(ChartsQuery),
+    params(
+        ChartsQuery,
+        LanguageQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Used when `lang` is not given, e.g. `hi-IN,hi;q=0.9`"),
+        FieldsQuery
+    ),
     responses(
-        (status = 200, description = "List of top charts playlists", body = Vec<Playlist>),
-        (status = 404, description = "No charts found", body = ApiError)
+        (status = 200, description = "List of top charts playlists", body = Vec<Playlist>, headers(
+            ("Content-Language" = String, description = "ISO codes of the languages the response was built for"),
+            ("X-Language-Source" = String, description = "`query`, `accept-language` or `default`")
+        )),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No charts found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "charts"
 )]
 pub async fn get_charts(
-    Query(params): Query<ChartsQuery>,
-) -> Result<Json<Vec<Playlist>>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<ChartsQuery>,
+    preferred: PreferredLanguages,
+) -> Result<impl IntoResponse, ApiError> {
     let api = BaseApi::new();
-    // Validate and normalize language
-    let language = match params.language {
-        Some(lang) => formatting::validate_language(&lang),
-        None => "Telugu".to_string(),
-    };
+    let chosen = preferred.resolve(Language::Telugu);
+
+    // One chart list per language, taking turns so every language is represented
+    let mut charts = Vec::new();
+    for entities in join_all(
+        chosen
+            .languages
+            .as_slice()
+            .iter()
+            .map(|language| fetch_chart_entities(&api, *language)),
+    )
+    .await
+    {
+        charts.push(entities?);
+    }
+
+    let mut playlist_list: Vec<Playlist> = Vec::new();
+
+    let limit = params.limit.unwrap_or(10);
+
+    for entity in formatting::interleave(charts) {
+        if playlist_list.len() >= limit {
+            break;
+        }
+        if let Some(processed_playlist) = format_chart_entity(&entity) {
+            if !playlist_list
+                .iter()
+                .any(|p| p.seokey == processed_playlist.seokey)
+            {
+                playlist_list.push(processed_playlist);
+            }
+        }
+    }
+
+    if playlist_list.is_empty() {
+        return Err(ApiError::not_found("top charts"));
+    }
+
+    Ok((chosen.headers(), Json(playlist_list)))
+}
 
+async fn fetch_chart_entities(
+    api: &BaseApi,
+    language: Language,
+) -> Result<Vec<GaanaChartEntity>, ApiError> {
     // Build charts URL with language filter
     let url = format!(
         "https://gaana.com/apiv2?page=0&type=miscTopCharts&language={}",
-        encode(&language)
+        encode(language.name())
     );
 
-    match api.make_request(&url).await {
-        Ok(response) => {
-            let charts_response: Result<GaanaChartsResponse, _> =
-                serde_json::from_value(response.clone());
-
-            match charts_response {
-                Ok(data) => {
-                    if let Some(entities) = data.entities {
-                        let mut playlist_list = Vec::new();
-                        
-                        let limit = params.limit.unwrap_or(10);
-                        let entities_to_process = entities.into_iter().take(limit);
-
-                        for entity in entities_to_process {
-                            if let Some(processed_playlist) = format_chart_entity(&entity) {
-                                playlist_list.push(processed_playlist);
-                            }
-                        }
-
-                        if playlist_list.is_empty() {
-                            return Err((
-                                StatusCode::NOT_FOUND,
-                                Json(ApiError::not_found("top charts")),
-                            ));
-                        }
-
-                        Ok(Json(playlist_list))
-                    } else {
-                        Err((
-                            StatusCode::NOT_FOUND,
-                            Json(ApiError::not_found("top charts entities")),
-                        ))
-                    }
-                }
-                Err(_) => Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::internal_error("Failed to parse charts response")),
-                )),
-            }
-        }
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-        )),
-    }
+    let response = api.make_request(&url).await?;
+    let data: GaanaChartsResponse = serde_json::from_value(response).map_err(|e| {
+        ApiError::upstream_schema_changed(&format!("Failed to parse charts response: {}", e))
+    })?;
+
+    data.entities
+        .ok_or_else(|| ApiError::not_found("top charts entities"))
 }
 
 // Helper function to format chart entity similar to Python's format_json_charts
@@ -93,9 +116,10 @@ fn format_chart_entity(entity: &GaanaChartEntity) -> Option<Playlist> {
     let seokey = entity.seokey.as_ref()?.clone();
     let playlist_id = formatting::extract_id(&entity.entity_id);
     let title = entity.name.as_ref()?.clone();
-    
+
     // Extract is_explicit from entity_info[6] if available (unused in current implementation but available for future use)
-    let _is_explicit = entity.entity_info
+    let _is_explicit = entity
+        .entity_info
         .as_ref()
         .and_then(|info| info.get(6))
         .and_then(|item| item.value.as_ref())
@@ -103,23 +127,22 @@ fn format_chart_entity(entity: &GaanaChartEntity) -> Option<Playlist> {
         .unwrap_or(0);
 
     // Extract play_count from last entity_info item if available
-    let play_count = entity.entity_info
+    let play_count = entity
+        .entity_info
         .as_ref()
-        .and_then(|info| info.last())
+        .and_then(|info| info.iter().next_back())
         .and_then(|item| item.value.as_ref())
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
     // Create images from atwj
-    let images = if let Some(artwork_url) = &entity.atwj {
-        Some(Images::new(
+    let images = entity.atwj.as_ref().map(|artwork_url| {
+        Images::new(
             Some(artwork_url.replace("size_m.jpg", "size_l.jpg")),
             Some(artwork_url.clone()),
             Some(artwork_url.replace("size_m.jpg", "size_s.jpg")),
-        ))
-    } else {
-        None
-    };
+        )
+    });
 
     Some(Playlist {
         seokey: seokey.clone(),
@@ -131,6 +154,8 @@ fn format_chart_entity(entity: &GaanaChartEntity) -> Option<Playlist> {
         favorite_count: formatting::extract_int(&entity.favorite_count),
         playlist_url: format!("https://gaana.com/playlist/{}", seokey),
         images,
-        tracks_url: format!("/playlists/info?seokey={}", seokey),
+        tracks_url: format!("/playlists/info?seokey={}&tracks_only=true", seokey),
+        total_tracks: None,
+        tracks: None,
     })
 }
@@ -0,0 +1,208 @@
+use axum::{extract::Path, response::Json};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::api::base::BaseApi;
+use crate::models::{
+    error::ApiError, genre::Genre, language::Languages, playlist::Playlist, results::Paginated,
+    song::Song,
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::filters::Filterable;
+use crate::utils::formatting;
+use crate::utils::genres::{self, GenreEntry};
+use crate::utils::languages::LanguageQuery;
+use crate::utils::validation::{self, Validate, ValidatedQuery};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GenreBrowseQuery {
+    /// Zero-based page number
+    page: Option<usize>,
+    /// Results per page (default 10)
+    limit: Option<usize>,
+}
+
+impl Validate for GenreBrowseQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.page = Some(validation::validate_page(self.page)?);
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
+
+/// List the genres and moods that can be browsed
+#[utoipa::path(
+    get,
+    path = "/genres",
+    params(LanguageQuery, FieldsQuery),
+    responses(
+        (status = 200, description = "Genres and moods, optionally narrowed to languages", body = Vec<Genre>),
+        (status = 400, description = "Invalid query parameters", body = ApiError)
+    ),
+    tag = "genres"
+)]
+pub async fn list_genres(ValidatedQuery(lang): ValidatedQuery<LanguageQuery>) -> Json<Vec<Genre>> {
+    Json(genres::available(lang.languages.as_ref()))
+}
+
+/// Browse songs in a genre or mood
+#[utoipa::path(
+    get,
+    path = "/genres/{id}/songs",
+    params(
+        ("id" = String, Path, description = "Genre id from `/genres`, e.g. `romantic`"),
+        GenreBrowseQuery,
+        LanguageQuery,
+        FieldsQuery,
+        IncludeQuery
+    ),
+    responses(
+        (status = 200, description = "One page of songs in the genre", body = SongPage),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Unknown genre or no songs in it", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "genres"
+)]
+pub async fn get_genre_songs(
+    Path(id): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<GenreBrowseQuery>,
+    ValidatedQuery(lang): ValidatedQuery<LanguageQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<Paginated<Song>>, ApiError> {
+    let genre = find_genre(&id)?;
+    let api = BaseApi::new();
+    let page = params.page.unwrap_or(0);
+    let limit = params.limit.unwrap_or(10);
+
+    let (seokeys, has_more) =
+        genre_page(&api, genre, lang.languages.as_ref(), "track", page, limit).await?;
+    let mut songs = api.fetch_songs(seokeys).await;
+
+    // Search is keyword based, so put songs Gaana actually tags with the genre first
+    songs.results.sort_by_key(|song| {
+        !song
+            .genres()
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(genre.name))
+    });
+
+    if songs.is_empty() && page == 0 {
+        return Err(ApiError::no_results(&format!(
+            "Gaana has no songs for genre '{}'",
+            genre.id
+        )));
+    }
+    Ok(Json(Paginated::new(
+        songs.with_missing(include.wants_missing()),
+        page,
+        limit,
+        has_more,
+    )))
+}
+
+/// Browse playlists for a genre or mood
+#[utoipa::path(
+    get,
+    path = "/genres/{id}/playlists",
+    params(
+        ("id" = String, Path, description = "Genre id from `/genres`, e.g. `romantic`"),
+        GenreBrowseQuery,
+        LanguageQuery,
+        FieldsQuery,
+        IncludeQuery
+    ),
+    responses(
+        (status = 200, description = "One page of playlists for the genre", body = PlaylistPage),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Unknown genre or no playlists for it", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "genres"
+)]
+pub async fn get_genre_playlists(
+    Path(id): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<GenreBrowseQuery>,
+    ValidatedQuery(lang): ValidatedQuery<LanguageQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<Paginated<Playlist>>, ApiError> {
+    let genre = find_genre(&id)?;
+    let api = BaseApi::new();
+    let page = params.page.unwrap_or(0);
+    let limit = params.limit.unwrap_or(10);
+
+    let (seokeys, has_more) = genre_page(
+        &api,
+        genre,
+        lang.languages.as_ref(),
+        "playlist",
+        page,
+        limit,
+    )
+    .await?;
+    let playlists = api.fetch_playlists(seokeys).await;
+
+    if playlists.is_empty() && page == 0 {
+        return Err(ApiError::no_results(&format!(
+            "Gaana has no playlists for genre '{}'",
+            genre.id
+        )));
+    }
+    Ok(Json(Paginated::new(
+        playlists.with_missing(include.wants_missing()),
+        page,
+        limit,
+        has_more,
+    )))
+}
+
+/// One page of search results for a genre, taking turns between languages when several are given.
+/// Every language is read from the start so the merged list, and so each page of it, stays stable.
+async fn genre_page(
+    api: &BaseApi,
+    genre: &GenreEntry,
+    languages: Option<&Languages>,
+    sec_type: &str,
+    page: usize,
+    limit: usize,
+) -> Result<(Vec<String>, bool), ApiError> {
+    let keywords: Vec<String> = match languages {
+        Some(languages) => languages
+            .as_slice()
+            .iter()
+            .map(|language| genre.keyword(Some(*language)))
+            .collect(),
+        None => vec![genre.keyword(None)],
+    };
+    // One result past the page tells us whether there are more
+    let offset = page * limit;
+    let want = offset + limit + 1;
+    let mut lists = Vec::new();
+    for (_, result) in api
+        .fan_out_keyed(keywords, |keyword| async move {
+            api.search_seokeys(&keyword, sec_type, want).await
+        })
+        .await
+    {
+        lists.push(result?);
+    }
+    let mut seokeys: Vec<String> = Vec::new();
+    for seokey in formatting::interleave(lists) {
+        if !seokeys.contains(&seokey) {
+            seokeys.push(seokey);
+        }
+    }
+    let has_more = seokeys.len() > offset + limit;
+    Ok((
+        seokeys.into_iter().skip(offset).take(limit).collect(),
+        has_more,
+    ))
+}
+
+fn find_genre(id: &str) -> Result<&'static GenreEntry, ApiError> {
+    genres::find(id).ok_or_else(|| ApiError::unknown_genre(id.trim()))
+}
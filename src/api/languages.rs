@@ -0,0 +1,19 @@
+use axum::response::Json;
+
+use crate::models::language::Language;
+use crate::models::language::LanguageInfo;
+use crate::utils::fields::FieldsQuery;
+
+/// List the languages accepted by `lang`, with their native names and ISO codes
+#[utoipa::path(
+    get,
+    path = "/languages",
+    params(FieldsQuery),
+    responses(
+        (status = 200, description = "Supported languages", body = Vec<LanguageInfo>)
+    ),
+    tag = "languages"
+)]
+pub async fn list_languages() -> Json<Vec<LanguageInfo>> {
+    Json(Language::ALL.into_iter().map(LanguageInfo::from).collect())
+}
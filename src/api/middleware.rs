@@ -0,0 +1,109 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Query, Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::models::error::{ApiError, ProblemDetails};
+use crate::utils::fields::{FieldsQuery, Shape};
+
+/// Header carrying the per-request id set by the request-id layer
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Default error body format, configured with the `ERROR_FORMAT` environment variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Json,
+    Problem,
+}
+
+impl ErrorFormat {
+    pub fn from_env_value(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "problem" | "problem+json" | "rfc7807" => ErrorFormat::Problem,
+            _ => ErrorFormat::Json,
+        }
+    }
+}
+
+/// Re-render `ApiError` responses as `application/problem+json` when the client
+/// asks for it in `Accept` or the server is configured to always use it
+pub async fn problem_details(
+    State(format): State<ErrorFormat>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let wants_problem = format == ErrorFormat::Problem || accepts_problem_json(request.headers());
+    let instance = request.uri().to_string();
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    let response = next.run(request).await;
+    if !wants_problem {
+        return response;
+    }
+
+    match response.extensions().get::<ApiError>() {
+        Some(error) => ProblemDetails::from_error(error, &instance, request_id).into_response(),
+        None => response,
+    }
+}
+
+/// Apply `fields=`/`exclude=` to successful JSON responses of every endpoint
+pub async fn sparse_fieldsets(request: Request, next: Next) -> Response {
+    let query = Query::<FieldsQuery>::try_from_uri(request.uri())
+        .map(|Query(q)| q)
+        .unwrap_or_default();
+    let shape = match Shape::from_query(&query) {
+        Ok(shape) => shape,
+        Err(e) => return e.into_response(),
+    };
+
+    let response = next.run(request).await;
+    if shape.is_empty() || !response.status().is_success() || !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ApiError::internal_error(&format!("Failed to read response body: {}", e))
+                .into_response()
+        }
+    };
+    let shaped = match serde_json::from_slice(&bytes) {
+        Ok(value) => serde_json::to_vec(&shape.apply(value)).unwrap_or_default(),
+        Err(_) => bytes.to_vec(),
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(shaped))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+fn accepts_problem_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|media| {
+            media
+                .split(';')
+                .next()
+                .map(|m| m.trim().eq_ignore_ascii_case(ProblemDetails::CONTENT_TYPE))
+                .unwrap_or(false)
+        })
+}
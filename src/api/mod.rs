@@ -2,7 +2,13 @@ pub mod albums;
 pub mod artists;
 pub mod base;
 pub mod charts;
+pub mod genres;
+pub mod languages;
+pub mod middleware;
 pub mod newreleases;
 pub mod playlists;
+pub mod radio;
+pub mod resolve;
+pub mod search;
 pub mod songs;
 pub mod trending;
@@ -1,118 +1,135 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::{IntoResponse, Json};
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
-use utoipa::IntoParams;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::api::base::BaseApi;
-use crate::models::{error::ApiError, song::*, album::Album};
+use crate::models::{
+    album::Album, error::ApiError, language::Language, results::MissingItem, song::*,
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
 use crate::utils::formatting;
+use crate::utils::languages::{LanguageQuery, PreferredLanguages};
+use crate::utils::validation::{self, Validate, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct NewReleasesQuery {
-    #[serde(rename = "lang")]
-    language: Option<String>,
     limit: Option<usize>,
+    /// Fail the whole request if any release cannot be resolved
+    strict: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+impl Validate for NewReleasesQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct NewReleasesResponse {
     pub songs: Vec<Song>,
     pub albums: Vec<Album>,
+    /// Only with `include=missing`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<MissingItem>>,
 }
 
 /// Get new song and album releases by language
 #[utoipa::path(
     get,
     path = "/newreleases",
-    params(NewReleasesQuery),
+    params(
+        NewReleasesQuery,
+        LanguageQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Used when `lang` is not given, e.g. `hi-IN,hi;q=0.9`"),
+        FieldsQuery,
+        IncludeQuery
+    ),
     responses(
-        (status = 200, description = "List of new releases", body = NewReleasesResponse),
-        (status = 404, description = "No new releases found", body = ApiError)
+        (status = 200, description = "List of new releases", body = NewReleasesResponse, headers(
+            ("Content-Language" = String, description = "ISO codes of the languages the response was built for"),
+            ("X-Language-Source" = String, description = "`query`, `accept-language` or `default`")
+        )),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No new releases found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "newreleases"
 )]
 pub async fn get_new_releases(
-    Query(params): Query<NewReleasesQuery>,
-) -> Result<Json<NewReleasesResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<NewReleasesQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+    preferred: PreferredLanguages,
+) -> Result<impl IntoResponse, ApiError> {
     let api = BaseApi::new();
+    let chosen = preferred.resolve(Language::Telugu);
+    let languages = &chosen.languages;
 
-    // Validate and normalize language
-    let language = match params.language {
-        Some(lang) => formatting::validate_language(&lang),
-        None => "Telugu".to_string(),
-    };
-
-    let url = format!(
-        "https://gaana.com/apiv2?page=0&type=miscNewRelease&language={}",
-        urlencoding::encode(&language)
-    );
+    // One feed per language, taking turns so every language is represented
+    let responses = join_all(languages.as_slice().iter().map(|language| {
+        let url = format!(
+            "https://gaana.com/apiv2?page=0&type=miscNewRelease&language={}",
+            urlencoding::encode(language.name())
+        );
+        let api = &api;
+        async move { api.make_request_flexible(&url).await }
+    }))
+    .await;
+    let mut feeds = Vec::new();
+    for response in responses {
+        let entities = response?
+            .get("entities")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        feeds.push(entities);
+    }
 
-    match api.make_request_flexible(&url).await {
-        Ok(response) => {
-            // Gather track and album seokeys from entities
-            let limit = params.limit.unwrap_or(10);
-            let mut track_seokeys = Vec::new();
-            let mut album_seokeys = Vec::new();
-            if let Some(entities) = response.get("entities").and_then(|v| v.as_array()) {
-                for (i, entity) in entities.iter().enumerate() {
-                    if i >= limit {
-                        break;
-                    }
-                    if let (Some(entity_type), Some(seokey)) = (
-                        entity.get("entity_type").and_then(|v| v.as_str()),
-                        entity.get("seokey").and_then(|v| v.as_str()),
-                    ) {
-                        match entity_type {
-                            "TR" => track_seokeys.push(seokey.to_string()),
-                            "AL" => album_seokeys.push(seokey.to_string()),
-                            _ => {}
-                        }
-                    }
-                }
-            }
-            // Return 404 if no results
-            if track_seokeys.is_empty() && album_seokeys.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found(&format!("new releases in {}", language))),
-                ));
-            }
-            // Fetch track details
-            let mut songs = Vec::new();
-            for key in track_seokeys {
-                let detail_url = format!(
-                    "https://gaana.com/apiv2?type=songDetail&seokey={}",
-                    urlencoding::encode(&key)
-                );
-                if let Ok(detail_resp) = api.make_request_flexible(&detail_url).await {
-                    if let Some(tracks) = detail_resp.get("tracks").and_then(|v| v.as_array()) {
-                        for track_data in tracks {
-                            if let Ok(track) = serde_json::from_value::<GaanaTrack>(track_data.clone()) {
-                                if let Some(song) = api.process_gaana_track(&track) {
-                                    songs.push(song);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            // Fetch album details
-            let mut albums = Vec::new();
-            for key in album_seokeys {
-                let detail_url = format!(
-                    "https://gaana.com/apiv2?type=albumDetail&seokey={}",
-                    urlencoding::encode(&key)
-                );
-                if let Ok(detail_resp) = api.make_request_flexible(&detail_url).await {
-                    if let Some(processed) = api.process_gaana_album_response(&detail_resp, false) {
-                        albums.push(processed);
-                    }
-                }
+    // Gather track and album seokeys from entities
+    let limit = params.limit.unwrap_or(10);
+    let mut track_seokeys = Vec::new();
+    let mut album_seokeys = Vec::new();
+    for entity in formatting::interleave(feeds) {
+        if track_seokeys.len() + album_seokeys.len() >= limit {
+            break;
+        }
+        if let (Some(entity_type), Some(seokey)) = (
+            entity.get("entity_type").and_then(|v| v.as_str()),
+            entity.get("seokey").and_then(|v| v.as_str()),
+        ) {
+            let seokeys = match entity_type {
+                "TR" => &mut track_seokeys,
+                "AL" => &mut album_seokeys,
+                _ => continue,
+            };
+            if !seokeys.iter().any(|s| s == seokey) {
+                seokeys.push(seokey.to_string());
             }
-            Ok(Json(NewReleasesResponse { songs, albums }))
         }
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-        )),
     }
+    // Return 404 if no results
+    if track_seokeys.is_empty() && album_seokeys.is_empty() {
+        return Err(ApiError::not_found(&format!(
+            "new releases in {}",
+            languages.join()
+        )));
+    }
+    // Fetch track and album details
+    let strict = params.strict.unwrap_or(false);
+    let songs = api.fetch_songs(track_seokeys).await.check_strict(strict)?;
+    let albums = api.fetch_albums(album_seokeys).await.check_strict(strict)?;
+
+    let mut missing = songs.missing;
+    missing.extend(albums.missing);
+    Ok((
+        chosen.headers(),
+        Json(NewReleasesResponse {
+            songs: songs.results,
+            albums: albums.results,
+            missing: include.wants_missing().then_some(missing),
+        }),
+    ))
 }
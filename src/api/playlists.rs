@@ -1,52 +1,205 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
-use serde::Deserialize;
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 
 use crate::api::base::BaseApi;
-use crate::models::{error::ApiError, song::Song};
+use crate::models::{
+    error::ApiError,
+    playlist::{GaanaPlaylist, Playlist},
+    results::{Paginated, ResultList},
+    search::{EntityType, SearchDetail, SearchHit},
+    song::Song,
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::formatting;
+use crate::utils::validation::{self, Validate, ValidatedQuery};
 use urlencoding::encode;
-use serde_json;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchPlaylistsQuery {
+    query: String,
+    limit: Option<usize>,
+    /// Fail the whole request if any result cannot be resolved
+    strict: Option<bool>,
+    /// `none` or `basic` return search hits without per-result detail calls (default `full`)
+    #[param(inline)]
+    detail: Option<SearchDetail>,
+}
+
+impl Validate for SearchPlaylistsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.query = validation::validate_query("query", &self.query)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
+}
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct PlaylistInfoQuery {
-    seokey: String,
+    seokey: Option<String>,
+    /// Numeric `playlist_id`, as an alternative to `seokey`
+    id: Option<String>,
+    /// Fail the whole request if any track cannot be resolved
+    strict: Option<bool>,
+    /// Zero-based page of tracks
+    page: Option<usize>,
+    /// Tracks per page (default 20)
+    limit: Option<usize>,
+    /// Return only the page of tracks, without playlist metadata
+    tracks_only: Option<bool>,
 }
 
-/// Get detailed information about a specific playlist
+impl Validate for PlaylistInfoQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        self.page = Some(validation::validate_page(self.page)?);
+        self.limit = Some(validation::validate_limit(self.limit, 20)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum PlaylistResponse {
+    Playlists(ResultList<Playlist>),
+    Hits(ResultList<SearchHit>),
+    Playlist(Box<Playlist>),
+    Tracks(Paginated<Song>),
+}
+
+/// Search for playlists by name
+#[utoipa::path(
+    get,
+    path = "/playlists/search",
+    params(SearchPlaylistsQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "List of playlists matching the search query (search hits with detail=none|basic); include=missing wraps them as { results, missing }", body = [Playlist]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No playlists found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "playlists"
+)]
+pub async fn search_playlists(
+    ValidatedQuery(params): ValidatedQuery<SearchPlaylistsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<PlaylistResponse>, ApiError> {
+    let detail = params.detail.unwrap_or_default();
+    let limit = params.limit.unwrap_or(10);
+    let api = BaseApi::new();
+
+    let search_url = format!(
+        "https://gaana.com/apiv2?country=IN&page=0&secType=playlist&type=search&keyword={}",
+        encode(&params.query)
+    );
+
+    let response = api.make_request_flexible(&search_url).await.map_err(|e| {
+        eprintln!("Playlist search request error: {}", e);
+        e
+    })?;
+
+    if detail != SearchDetail::Full {
+        let hits = api.search_hits(
+            formatting::first_search_group(&response),
+            EntityType::Playlist,
+            detail,
+            limit,
+            include.wants_missing(),
+        );
+        if hits.is_empty() {
+            return Err(ApiError::not_found(&params.query));
+        }
+        return Ok(Json(PlaylistResponse::Hits(hits)));
+    }
+
+    // Playlist hits come back in the same `gr[0].gd` shape as songs and albums
+    let playlist_ids: Vec<String> = response
+        .get("gr")
+        .and_then(|gr| gr.as_array())
+        .and_then(|groups| groups.first())
+        .and_then(|group| group.get("gd"))
+        .and_then(|gd| gd.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("seo").and_then(|s| s.as_str()))
+                .take(limit)
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if playlist_ids.is_empty() {
+        return Err(ApiError::not_found(
+            "No playlists found for the given query",
+        ));
+    }
+
+    let playlists = api
+        .fetch_playlists(playlist_ids)
+        .await
+        .check_strict(params.strict.unwrap_or(false))?;
+
+    if playlists.is_empty() {
+        return Err(ApiError::not_found("No valid playlist data found"));
+    }
+
+    Ok(Json(PlaylistResponse::Playlists(
+        playlists.with_missing(include.wants_missing()),
+    )))
+}
+
+/// Get a playlist's metadata with one page of its tracks embedded
 #[utoipa::path(
     get,
     path = "/playlists/info",
-    params(PlaylistInfoQuery),
+    params(PlaylistInfoQuery, FieldsQuery, IncludeQuery),
     responses(
-        (status = 200, description = "List of songs in the playlist", body = Vec<Song>),
-        (status = 404, description = "Playlist not found", body = ApiError)
+        (status = 200, description = "Playlist with a page of its tracks, or with tracks_only=true just that page", body = Playlist),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Playlist not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "playlists"
 )]
 pub async fn get_playlist_info(
-    Query(params): Query<PlaylistInfoQuery>,
-) -> Result<Json<Vec<Song>>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<PlaylistInfoQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<PlaylistResponse>, ApiError> {
     let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Playlist,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
+    let tracks_only = params.tracks_only.unwrap_or(false);
+    let page = params.page.unwrap_or(0);
+    let limit = params.limit.unwrap_or(20);
+
     // Build playlist detail URL
     let url = format!(
         "https://gaana.com/apiv2?type=playlistDetail&seokey={}",
-        encode(&params.seokey)
+        encode(&seokey)
     );
 
     // Fetch playlist details
-    let response = match api.make_request_flexible(&url).await {
-        Ok(res) => res,
-        Err(e) => {
-            eprintln!("Failed to get playlist info: {}", e);
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(ApiError::not_found(&format!(
-                    "Playlist with seokey '{}' not found",
-                    params.seokey
-                ))),
-            ));
-        }
-    };
+    let response = api.make_request_flexible(&url).await.map_err(|e| {
+        eprintln!("Failed to get playlist info: {}", e);
+        e
+    })?;
+
+    let playlist = response
+        .get("playlist")
+        .and_then(|p| serde_json::from_value::<GaanaPlaylist>(p.clone()).ok());
+    if playlist.is_none() && !tracks_only {
+        return Err(ApiError::invalid_seokey(&seokey));
+    }
 
     // Extract seokeys from playlist tracks
     let mut track_seokeys = Vec::new();
@@ -57,50 +210,44 @@ pub async fn get_playlist_info(
             }
         }
     }
-    if track_seokeys.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiError::not_found(&format!(
-                "No tracks found for playlist '{}'",
-                params.seokey
-            ))),
-        ));
+    if tracks_only && track_seokeys.is_empty() {
+        return Err(ApiError::no_results(&format!(
+            "Playlist '{}' has no tracks",
+            seokey
+        )));
     }
-    
-    // Fetch detailed info for each track
-    let mut songs = Vec::new();
-    for seokey in track_seokeys {
-        let detail_url = format!(
-            "https://gaana.com/apiv2?type=songDetail&seokey={}",
-            encode(&seokey)
-        );
-        match api.make_request_flexible(&detail_url).await {
-            Ok(detail_response) => {
-                if let Some(arr) = detail_response.get("tracks").and_then(|t| t.as_array()) {
-                    if let Some(item) = arr.first() {
-                        if let Ok(track) = serde_json::from_value::<crate::models::song::GaanaTrack>(
-                            item.clone()
-                        ) {
-                            if let Some(song) = api.process_gaana_track(&track) {
-                                songs.push(song);
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to get details for track {}: {}", seokey, e);
+
+    // Fetch detailed info for the requested page of tracks only
+    let total = track_seokeys.len();
+    let page_seokeys: Vec<String> = track_seokeys
+        .into_iter()
+        .skip(page * limit)
+        .take(limit)
+        .collect();
+    let has_more = (page + 1) * limit < total;
+    let songs = api
+        .fetch_songs(page_seokeys)
+        .await
+        .check_strict(params.strict.unwrap_or(false))?
+        .with_missing(include.wants_missing());
+
+    let tracks = Paginated::new(songs, page, limit, has_more);
+    match playlist {
+        Some(playlist) if !tracks_only => {
+            let mut processed = api
+                .process_gaana_playlist(&playlist, Some(tracks))
+                .ok_or_else(|| ApiError::invalid_seokey(&seokey))?;
+            processed.total_tracks = processed.total_tracks.or(Some(total as i32));
+            Ok(Json(PlaylistResponse::Playlist(Box::new(processed))))
+        }
+        _ => {
+            if tracks.results.is_empty() {
+                return Err(ApiError::no_results(&format!(
+                    "None of the tracks of playlist '{}' could be resolved",
+                    seokey
+                )));
             }
+            Ok(Json(PlaylistResponse::Tracks(tracks)))
         }
     }
-    if songs.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiError::not_found(&format!(
-                "No valid song data found for playlist '{}'",
-                params.seokey
-            ))),
-        ));
-    }
-    Ok(Json(songs))
 }
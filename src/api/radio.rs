@@ -0,0 +1,266 @@
+use axum::response::Json;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::api::base::BaseApi;
+use crate::models::{
+    error::ApiError,
+    radio::{RadioResponse, RadioSeed, RadioSeedType, RadioState, FRONTIER_SIZE},
+    song::Song,
+};
+use crate::utils::fields::FieldsQuery;
+use crate::utils::filters::Filterable;
+use crate::utils::genres;
+use crate::utils::recommend;
+use crate::utils::validation::{self, Validate, ValidatedQuery, MAX_LIMIT};
+
+/// Similar artists whose top tracks join an artist station
+const SEED_SIMILAR_ARTISTS: usize = 3;
+
+/// Candidates gathered per source for each batch
+const CANDIDATES_PER_SOURCE: usize = 20;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RadioQuery {
+    /// Start a station from this song's seokey
+    seed_song: Option<String>,
+    /// Start a station from this artist's seokey
+    seed_artist: Option<String>,
+    /// Start a station from a genre or mood: an id from `/genres` or any genre name
+    seed_genre: Option<String>,
+    /// `next_token` from the previous batch, instead of a seed
+    token: Option<String>,
+    /// Songs per batch (default 20)
+    limit: Option<usize>,
+}
+
+impl Validate for RadioQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.limit = Some(validation::validate_limit(self.limit, 20)?);
+        if let Some(song) = &self.seed_song {
+            self.seed_song = Some(validation::validate_seokey("seed_song", song)?);
+        }
+        if let Some(artist) = &self.seed_artist {
+            self.seed_artist = Some(validation::validate_seokey("seed_artist", artist)?);
+        }
+        if let Some(genre) = &self.seed_genre {
+            self.seed_genre = Some(validation::validate_query("seed_genre", genre)?);
+        }
+        let seeds = [&self.seed_song, &self.seed_artist, &self.seed_genre]
+            .iter()
+            .filter(|seed| seed.is_some())
+            .count();
+        match (seeds, &self.token) {
+            (1, None) => Ok(()),
+            (0, Some(token)) => decode_token(token).map(|_| ()),
+            (0, None) => Err(ApiError::invalid_parameter(
+                "seed_song",
+                "Pass one of seed_song, seed_artist, seed_genre or token",
+            )),
+            (_, None) => Err(ApiError::invalid_parameter(
+                "seed_song",
+                "Pass only one of seed_song, seed_artist or seed_genre",
+            )),
+            (_, Some(_)) => Err(ApiError::invalid_parameter(
+                "token",
+                "Pass either a seed or a token, not both",
+            )),
+        }
+    }
+}
+
+/// Decode a token and check its seed and frontier the way a fresh seed would be
+fn decode_token(token: &str) -> Result<RadioState, ApiError> {
+    let mut state = RadioState::decode(token)?;
+    state.seed.value = match state.seed.seed_type {
+        RadioSeedType::Song | RadioSeedType::Artist => {
+            validation::validate_seokey("token", &state.seed.value)?
+        }
+        RadioSeedType::Genre => validation::validate_query("token", &state.seed.value)?,
+    };
+    for seokey in &mut state.frontier {
+        *seokey = validation::validate_seokey("token", seokey)?;
+    }
+    Ok(state)
+}
+
+impl RadioQuery {
+    /// Fresh state for a seed, or the state carried by the token
+    fn into_state(self) -> Result<RadioState, ApiError> {
+        let seed = |seed_type, value| RadioState::new(RadioSeed { seed_type, value });
+        match (
+            self.seed_song,
+            self.seed_artist,
+            self.seed_genre,
+            self.token,
+        ) {
+            (Some(song), _, _, _) => Ok(seed(RadioSeedType::Song, song)),
+            (_, Some(artist), _, _) => Ok(seed(RadioSeedType::Artist, artist)),
+            (_, _, Some(genre), _) => Ok(seed(RadioSeedType::Genre, genre)),
+            (_, _, _, Some(token)) => decode_token(&token),
+            _ => Err(ApiError::invalid_parameter(
+                "seed_song",
+                "Pass one of seed_song, seed_artist, seed_genre or token",
+            )),
+        }
+    }
+}
+
+/// Generate a batch of an endless radio station
+#[utoipa::path(
+    get,
+    path = "/radio",
+    params(RadioQuery, FieldsQuery),
+    responses(
+        (status = 200, description = "A batch of songs and the token for the next one", body = RadioResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Seed not found or nothing to play from it", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "radio"
+)]
+pub async fn get_radio(
+    ValidatedQuery(params): ValidatedQuery<RadioQuery>,
+) -> Result<Json<RadioResponse>, ApiError> {
+    let api = BaseApi::new();
+    let limit = params.limit.unwrap_or(20);
+    let mut state = params.into_state()?;
+
+    // The seed is expanded on every batch so a station can recover when its frontier runs dry
+    let (seed_songs, frontier_songs) = tokio::join!(
+        seed_candidates(&api, &state.seed),
+        frontier_candidates(&api, &state.frontier)
+    );
+    let seed_songs = seed_songs?;
+    let mut candidates = seed_songs.clone();
+    candidates.extend(frontier_songs);
+    let mut batch = next_batch(&state, candidates, limit);
+
+    // Once everything around the seed has been played, start over from it rather than end the
+    // station: first avoiding only the latest batch, then repeating freely
+    if batch.is_empty() && !state.played.is_empty() {
+        state.restart(limit);
+        batch = next_batch(&state, seed_songs.clone(), limit);
+        if batch.is_empty() {
+            state.restart(0);
+            batch = next_batch(&state, seed_songs, limit);
+        }
+    }
+    if batch.is_empty() {
+        return Err(ApiError::no_results(&format!(
+            "Nothing to play from radio seed '{}'",
+            state.seed.value
+        )));
+    }
+
+    state.remember(batch.iter().map(|song| &song.track_id));
+    state.frontier = batch
+        .iter()
+        .rev()
+        .take(FRONTIER_SIZE)
+        .map(|song| song.seokey.clone())
+        .collect();
+    state.last_artist = batch.last().and_then(recommend::primary_artist);
+
+    Ok(Json(RadioResponse {
+        next_token: state.encode(),
+        seed: state.seed,
+        results: batch,
+    }))
+}
+
+/// Unplayed candidates, deduplicated and spread across artists
+fn next_batch(state: &RadioState, mut candidates: Vec<Song>, limit: usize) -> Vec<Song> {
+    candidates.retain(|song| !state.played.contains(&song.track_id));
+    let mut seen = Vec::new();
+    candidates.retain(|song| {
+        let fresh = !seen.contains(&song.track_id);
+        seen.push(song.track_id.clone());
+        fresh
+    });
+    if state.seed.seed_type == RadioSeedType::Genre {
+        let genre = genre_name(&state.seed.value);
+        candidates
+            .sort_by_key(|song| !song.genres().iter().any(|g| g.eq_ignore_ascii_case(&genre)));
+    }
+    recommend::balance_artists(candidates, limit, state.last_artist.as_deref())
+}
+
+/// Songs around the station's seed; fails only if the seed itself cannot be found
+async fn seed_candidates(api: &BaseApi, seed: &RadioSeed) -> Result<Vec<Song>, ApiError> {
+    match seed.seed_type {
+        RadioSeedType::Song => {
+            let song = api.fetch_song_cached(&seed.value).await?;
+            let (similar, neighbours) = tokio::join!(
+                api.fetch_similar_songs(&song.track_id, CANDIDATES_PER_SOURCE),
+                api.fetch_song_candidates(&song, CANDIDATES_PER_SOURCE)
+            );
+            let mut candidates = similar.map(|list| list.results).unwrap_or_default();
+            candidates.extend(neighbours);
+            Ok(candidates)
+        }
+        RadioSeedType::Artist => {
+            let artist = api.fetch_artist_cached(&seed.value).await?;
+            let similar = api
+                .fetch_similar_artists(&artist.artist_id, SEED_SIMILAR_ARTISTS)
+                .await
+                .map(|list| list.results)
+                .unwrap_or_default();
+            let artist_ids: Vec<String> = std::iter::once(artist.artist_id)
+                .chain(similar.into_iter().map(|a| a.artist_id))
+                .collect();
+            let top_tracks = api
+                .fan_out_keyed(artist_ids, |id| async move {
+                    api.fetch_artist_top_tracks(&id, CANDIDATES_PER_SOURCE)
+                        .await
+                })
+                .await;
+            Ok(top_tracks
+                .into_iter()
+                .filter_map(|(_, tracks)| tracks.ok())
+                .flat_map(|list| list.results)
+                .collect())
+        }
+        RadioSeedType::Genre => {
+            let seokeys = api
+                .search_seokeys(&genre_name(&seed.value), "track", MAX_LIMIT)
+                .await?;
+            Ok(api
+                .fan_out(seokeys, |seokey| async move {
+                    api.fetch_song_cached(&seokey).await
+                })
+                .await
+                .results)
+        }
+    }
+}
+
+/// Display name of a genre seed given as a `/genres` id, or the seed as typed
+fn genre_name(seed: &str) -> String {
+    genres::find(seed)
+        .map(|genre| genre.name.to_string())
+        .unwrap_or_else(|| seed.to_string())
+}
+
+/// Songs around the latest batch's songs; failures just contribute nothing
+async fn frontier_candidates(api: &BaseApi, frontier: &[String]) -> Vec<Song> {
+    let expanded = api
+        .fan_out_keyed(frontier.to_vec(), |seokey| async move {
+            let song = api.fetch_song_cached(&seokey).await?;
+            Ok(api
+                .fetch_song_candidates(&song, CANDIDATES_PER_SOURCE)
+                .await)
+        })
+        .await;
+    expanded
+        .into_iter()
+        .flat_map(|(seokey, outcome)| {
+            outcome.unwrap_or_else(|e| {
+                eprintln!("Skipping radio frontier '{}': {}", seokey, e.message);
+                Vec::new()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::base::BaseApi;
+use crate::models::{
+    album::Album, artist::Artist, error::ApiError, playlist::Playlist, search::EntityType,
+    song::Song,
+};
+use crate::utils::fields::FieldsQuery;
+use crate::utils::formatting;
+use crate::utils::validation::{self, Validate, ValidatedQuery};
+
+/// Longest URL accepted for resolving
+const MAX_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ResolveQuery {
+    /// Gaana song, album, artist or playlist link, e.g. `https://gaana.com/song/tum-hi-ho`
+    url: String,
+}
+
+impl Validate for ResolveQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        let url = self.url.trim();
+        if url.is_empty() {
+            return Err(ApiError::invalid_parameter("url", "Must not be empty"));
+        }
+        if url.len() > MAX_URL_LENGTH {
+            return Err(ApiError::invalid_parameter(
+                "url",
+                &format!("Must be at most {} characters", MAX_URL_LENGTH),
+            ));
+        }
+        self.url = url.to_string();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum ResolvedEntity {
+    Song(Box<Song>),
+    Album(Box<Album>),
+    Artist(Box<Artist>),
+    Playlist(Box<Playlist>),
+}
+
+/// The entity a Gaana link points at
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResolveResponse {
+    pub entity_type: EntityType,
+    pub entity: ResolvedEntity,
+}
+
+/// Resolve a pasted Gaana link to the song, album, artist or playlist it points at
+#[utoipa::path(
+    get,
+    path = "/resolve",
+    params(ResolveQuery, FieldsQuery),
+    responses(
+        (status = 200, description = "The linked entity with its type", body = ResolveResponse),
+        (status = 400, description = "Not a supported Gaana link", body = ApiError),
+        (status = 404, description = "The linked entity does not exist", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "resolve"
+)]
+pub async fn resolve(
+    ValidatedQuery(params): ValidatedQuery<ResolveQuery>,
+) -> Result<Json<ResolveResponse>, ApiError> {
+    let unsupported = || {
+        ApiError::invalid_parameter(
+            "url",
+            "Must be a gaana.com song, album, artist or playlist link",
+        )
+    };
+    let url = formatting::parse_gaana_url(&params.url).ok_or_else(unsupported)?;
+    let api = BaseApi::new();
+
+    // Share links carry no seokey of their own; follow them to the page they open
+    let (entity_type, seokey) = match formatting::extract_seokey_from_url(url.as_str()) {
+        Some(found) => found,
+        None => {
+            let expanded = api.expand_share_link(url.as_str()).await?;
+            formatting::extract_seokey_from_url(&expanded).ok_or_else(unsupported)?
+        }
+    };
+    let seokey = validation::validate_seokey("url", &seokey)?;
+
+    let entity = match entity_type {
+        EntityType::Song => ResolvedEntity::Song(Box::new(api.fetch_song(&seokey).await?)),
+        EntityType::Album => ResolvedEntity::Album(Box::new(api.fetch_album(&seokey, true).await?)),
+        EntityType::Artist => ResolvedEntity::Artist(Box::new(api.fetch_artist(&seokey).await?)),
+        EntityType::Playlist => {
+            ResolvedEntity::Playlist(Box::new(api.fetch_playlist(&seokey).await?))
+        }
+    };
+
+    Ok(Json(ResolveResponse {
+        entity_type,
+        entity,
+    }))
+}
@@ -0,0 +1,301 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Json},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::LazyLock;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::base::BaseApi;
+use crate::models::{
+    album::Album,
+    artist::Artist,
+    error::ApiError,
+    playlist::Playlist,
+    results::ResultList,
+    search::{EntityType, SearchDetail, SearchHit, Suggestion},
+    song::Song,
+};
+use crate::utils::cache::TtlCache;
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::formatting;
+use crate::utils::validation::{self, Validate, ValidatedQuery};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SearchQuery {
+    query: String,
+    /// Results per group unless overridden below (default 5)
+    limit: Option<usize>,
+    songs_limit: Option<usize>,
+    albums_limit: Option<usize>,
+    artists_limit: Option<usize>,
+    playlists_limit: Option<usize>,
+    /// Fail the whole request if any result cannot be resolved
+    strict: Option<bool>,
+    /// `none` or `basic` return search hits without per-result detail calls (default `full`)
+    #[param(inline)]
+    detail: Option<SearchDetail>,
+}
+
+impl Validate for SearchQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.query = validation::validate_query("query", &self.query)?;
+        let limit = validation::validate_limit(self.limit, 5)?;
+        self.limit = Some(limit);
+        for (param, group_limit) in [
+            ("songs_limit", &mut self.songs_limit),
+            ("albums_limit", &mut self.albums_limit),
+            ("artists_limit", &mut self.artists_limit),
+            ("playlists_limit", &mut self.playlists_limit),
+        ] {
+            *group_limit = Some(
+                validation::validate_limit(*group_limit, limit)
+                    .map_err(|e| ApiError::invalid_parameter(param, &e.message))?,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuggestQuery {
+    /// Text typed so far
+    q: String,
+    /// Maximum number of suggestions (default 8)
+    limit: Option<usize>,
+}
+
+impl Validate for SuggestQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.q = validation::validate_query("q", &self.q)?;
+        self.limit = Some(validation::validate_limit(self.limit, 8)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuggestResponse {
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// How long suggestions for a prefix are reused, in memory and by HTTP caches
+const SUGGEST_TTL: Duration = Duration::from_secs(600);
+
+/// Number of distinct prefixes kept in memory
+const SUGGEST_CACHE_CAPACITY: usize = 5000;
+
+static SUGGEST_CACHE: LazyLock<TtlCache<Vec<Suggestion>>> =
+    LazyLock::new(|| TtlCache::new(SUGGEST_TTL, SUGGEST_CACHE_CAPACITY));
+
+/// Search results grouped by entity type; `include=missing` wraps each list as `{ results, missing }`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchResponse {
+    #[schema(value_type = Vec<Song>)]
+    pub songs: ResultList<Song>,
+    #[schema(value_type = Vec<Album>)]
+    pub albums: ResultList<Album>,
+    #[schema(value_type = Vec<Artist>)]
+    pub artists: ResultList<Artist>,
+    #[schema(value_type = Vec<Playlist>)]
+    pub playlists: ResultList<Playlist>,
+}
+
+/// Search hits grouped by entity type, for `detail=none|basic`; `include=missing` wraps each list
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchHitsResponse {
+    #[schema(value_type = Vec<SearchHit>)]
+    pub songs: ResultList<SearchHit>,
+    #[schema(value_type = Vec<SearchHit>)]
+    pub albums: ResultList<SearchHit>,
+    #[schema(value_type = Vec<SearchHit>)]
+    pub artists: ResultList<SearchHit>,
+    #[schema(value_type = Vec<SearchHit>)]
+    pub playlists: ResultList<SearchHit>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum UnifiedSearchResponse {
+    Full(SearchResponse),
+    Hits(SearchHitsResponse),
+}
+
+/// Search songs, albums, artists and playlists at once
+#[utoipa::path(
+    get,
+    path = "/search",
+    params(SearchQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "Search results grouped by entity type (SearchHitsResponse with detail=none|basic)", body = SearchResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Nothing matched the query", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "search"
+)]
+pub async fn search(
+    ValidatedQuery(params): ValidatedQuery<SearchQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<UnifiedSearchResponse>, ApiError> {
+    let api = BaseApi::new();
+
+    // Without a secType Gaana returns one `gr` group per entity type
+    let search_url = format!(
+        "https://gaana.com/apiv2?country=IN&page=0&type=search&keyword={}",
+        urlencoding::encode(&params.query)
+    );
+    let response = api.make_request_flexible(&search_url).await.map_err(|e| {
+        eprintln!("Search request error: {}", e);
+        e
+    })?;
+
+    let detail = params.detail.unwrap_or_default();
+    if detail != SearchDetail::Full {
+        let hits = |entity_type, limit: Option<usize>| {
+            api.search_hits(
+                formatting::find_search_group(&response, entity_type),
+                entity_type,
+                detail,
+                limit.unwrap_or(5),
+                include.wants_missing(),
+            )
+        };
+        let response = SearchHitsResponse {
+            songs: hits(EntityType::Song, params.songs_limit),
+            albums: hits(EntityType::Album, params.albums_limit),
+            artists: hits(EntityType::Artist, params.artists_limit),
+            playlists: hits(EntityType::Playlist, params.playlists_limit),
+        };
+        if response.songs.is_empty()
+            && response.albums.is_empty()
+            && response.artists.is_empty()
+            && response.playlists.is_empty()
+        {
+            return Err(ApiError::not_found(&params.query));
+        }
+        return Ok(Json(UnifiedSearchResponse::Hits(response)));
+    }
+
+    let song_ids = formatting::extract_search_group_seokeys(
+        &response,
+        EntityType::Song,
+        params.songs_limit.unwrap_or(5),
+    );
+    let album_ids = formatting::extract_search_group_seokeys(
+        &response,
+        EntityType::Album,
+        params.albums_limit.unwrap_or(5),
+    );
+    let artist_ids = formatting::extract_search_group_seokeys(
+        &response,
+        EntityType::Artist,
+        params.artists_limit.unwrap_or(5),
+    );
+    let playlist_ids = formatting::extract_search_group_seokeys(
+        &response,
+        EntityType::Playlist,
+        params.playlists_limit.unwrap_or(5),
+    );
+
+    if song_ids.is_empty()
+        && album_ids.is_empty()
+        && artist_ids.is_empty()
+        && playlist_ids.is_empty()
+    {
+        return Err(ApiError::not_found(&params.query));
+    }
+
+    // Resolve every group at the same time
+    let (songs, albums, artists, playlists) = tokio::join!(
+        api.fetch_songs(song_ids),
+        api.fetch_albums(album_ids),
+        api.fetch_artists(artist_ids),
+        api.fetch_playlists(playlist_ids),
+    );
+
+    let strict = params.strict.unwrap_or(false);
+    Ok(Json(UnifiedSearchResponse::Full(SearchResponse {
+        songs: songs
+            .check_strict(strict)?
+            .with_missing(include.wants_missing()),
+        albums: albums
+            .check_strict(strict)?
+            .with_missing(include.wants_missing()),
+        artists: artists
+            .check_strict(strict)?
+            .with_missing(include.wants_missing()),
+        playlists: playlists
+            .check_strict(strict)?
+            .with_missing(include.wants_missing()),
+    })))
+}
+
+/// Suggest songs, albums, artists and playlists while the user types
+#[utoipa::path(
+    get,
+    path = "/search/suggest",
+    params(SuggestQuery, FieldsQuery),
+    responses(
+        (status = 200, description = "Lightweight suggestions; cacheable for ten minutes", body = SuggestResponse),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "search"
+)]
+pub async fn suggest(
+    ValidatedQuery(params): ValidatedQuery<SuggestQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let key = params.q.to_lowercase();
+    let suggestions = match SUGGEST_CACHE.get(&key) {
+        Some(cached) => cached,
+        None => {
+            let api = BaseApi::new();
+            let search_url = format!(
+                "https://gaana.com/apiv2?country=IN&page=0&type=search&keyword={}",
+                urlencoding::encode(&params.q)
+            );
+            let response = api.make_request_flexible(&search_url).await?;
+            let suggestions = suggestions_from_search(&response);
+            SUGGEST_CACHE.insert(key, suggestions.clone());
+            suggestions
+        }
+    };
+
+    let limit = params.limit.unwrap_or(8);
+    Ok((
+        [(
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", SUGGEST_TTL.as_secs()),
+        )],
+        Json(SuggestResponse {
+            suggestions: suggestions.into_iter().take(limit).collect(),
+        }),
+    ))
+}
+
+/// Build suggestions from every known search group, keeping Gaana's group order
+fn suggestions_from_search(response: &Value) -> Vec<Suggestion> {
+    let groups = match response.get("gr").and_then(|gr| gr.as_array()) {
+        Some(groups) => groups,
+        None => return Vec::new(),
+    };
+    groups
+        .iter()
+        .filter_map(|group| {
+            let entity_type = formatting::search_group_type(group)?;
+            let items = group.get("gd")?.as_array()?;
+            Some(
+                items
+                    .iter()
+                    .filter_map(move |item| Suggestion::from_search_item(item, entity_type)),
+            )
+        })
+        .flatten()
+        .collect()
+}
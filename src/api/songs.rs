@@ -1,199 +1,460 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::Json;
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 
 use crate::api::base::BaseApi;
-use crate::models::{error::ApiError, song::*};
+use crate::models::{
+    batch::{BatchRequest, BatchResponse},
+    error::{ApiError, ErrorCode},
+    lyrics::Lyrics,
+    results::ResultList,
+    search::{EntityType, SearchDetail, SearchHit},
+    song::*,
+};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::filters::{self, SearchFilters};
+use crate::utils::formatting;
+use crate::utils::fuzzy;
+use crate::utils::lyrics;
+use crate::utils::query::FieldedQuery;
+use crate::utils::recommend;
+use crate::utils::validation::{self, Validate, ValidatedJson, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct SearchSongsQuery {
+    /// Free text, optionally with `title:`, `artist:`, `album:` and `year:` terms,
+    /// e.g. `artist:"Arijit Singh" album:aashiqui year:2013`
     query: String,
     limit: Option<usize>,
+    /// Fail the whole request if any result cannot be resolved
+    strict: Option<bool>,
+    /// `none` or `basic` return search hits without per-result detail calls (default `full`)
+    #[param(inline)]
+    detail: Option<SearchDetail>,
+    /// Also search spelling and transliteration variants, then merge and re-rank by similarity
+    fuzzy: Option<bool>,
+}
+
+impl Validate for SearchSongsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.query = validation::validate_query("query", &self.query)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        let fielded = FieldedQuery::parse(&self.query)?.is_fielded();
+        if (fielded || self.fuzzy.unwrap_or(false))
+            && self.detail.unwrap_or_default() != SearchDetail::Full
+        {
+            return Err(ApiError::invalid_parameter(
+                "detail",
+                "Fielded and fuzzy queries require detail=full",
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct SongInfoQuery {
-    seokey: String,
+    seokey: Option<String>,
+    /// Numeric `track_id`, as an alternative to `seokey`
+    id: Option<String>,
+}
+
+impl Validate for SongInfoQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SongLyricsQuery {
+    seokey: Option<String>,
+    /// Numeric `track_id`, as an alternative to `seokey`
+    id: Option<String>,
+}
+
+impl Validate for SongLyricsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SimilarSongsQuery {
+    seokey: Option<String>,
+    /// Numeric `track_id`, as an alternative to `seokey`
+    id: Option<String>,
+    limit: Option<usize>,
+    /// Fail the whole request if any song cannot be resolved
+    strict: Option<bool>,
+}
+
+impl Validate for SimilarSongsQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        validation::validate_seokey_or_id(&mut self.seokey, &mut self.id)?;
+        self.limit = Some(validation::validate_limit(self.limit, 10)?);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum SongResponse {
-    Songs(Vec<Song>),
-    Song(Song),
-    Error(ApiError),
+    Songs(ResultList<Song>),
+    Hits(ResultList<SearchHit>),
+    Song(Box<Song>),
 }
 
 /// Search for songs by name/title
 #[utoipa::path(
     get,
     path = "/songs/search",
-    params(SearchSongsQuery),
+    params(SearchSongsQuery, SearchFilters, FieldsQuery, IncludeQuery),
     responses(
-        (status = 200, description = "List of songs matching the search query", body = Vec<Song>),
-        (status = 404, description = "No songs found", body = ApiError)
+        (status = 200, description = "List of songs matching the search query (search hits with detail=none|basic); include=missing wraps them as { results, missing }", body = [Song]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No songs found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "songs"
 )]
 pub async fn search_songs(
-    Query(params): Query<SearchSongsQuery>,
-) -> Result<Json<SongResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<SearchSongsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+    ValidatedQuery(filters): ValidatedQuery<SearchFilters>,
+) -> Result<Json<SongResponse>, ApiError> {
+    let detail = params.detail.unwrap_or_default();
+    let limit = params.limit.unwrap_or(10);
+    filters.check_detail(detail)?;
+    let query = FieldedQuery::parse(&params.query)?;
+    let fuzzy = params.fuzzy.unwrap_or(false);
     let api = BaseApi::new();
 
-    // Use the exact same endpoint as the Python version
-    let search_url = format!(
-        "https://gaana.com/apiv2?country=IN&page=0&secType=track&type=search&keyword={}",
-        urlencoding::encode(&params.query)
-    );
+    // Use the exact same endpoint as the Python version, with only the free text
+    let search_url = song_search_url(&query.keyword());
 
     eprintln!("Trying search endpoint: {}", search_url);
-    match api.make_request_flexible(&search_url).await {
-        Ok(response) => {
-            eprintln!("Got search response, extracting track IDs...");
-
-            // Extract track IDs from search response like the Python version does
-            let mut track_ids = Vec::new();
-            let limit = params.limit.unwrap_or(10);
-
-            // Parse the search response to get seokeys
-            if let Some(gr) = response.get("gr") {
-                if let Some(gr_array) = gr.as_array() {
-                    if let Some(first_group) = gr_array.first() {
-                        if let Some(gd) = first_group.get("gd") {
-                            if let Some(gd_array) = gd.as_array() {
-                                for (i, item) in gd_array.iter().enumerate() {
-                                    if i >= limit {
-                                        break;
-                                    }
-                                    if let Some(seo) = item.get("seo") {
-                                        if let Some(seo_str) = seo.as_str() {
-                                            track_ids.push(seo_str.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    let response = api.make_request_flexible(&search_url).await.map_err(|e| {
+        eprintln!("Search request error: {}", e);
+        e
+    })?;
+    eprintln!("Got search response, extracting track IDs...");
 
-            if track_ids.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No songs found for the given query")),
-                ));
-            }
+    if detail != SearchDetail::Full {
+        let hits = api.search_hits(
+            formatting::first_search_group(&response),
+            EntityType::Song,
+            detail,
+            limit,
+            include.wants_missing(),
+        );
+        if hits.is_empty() {
+            return Err(ApiError::not_found(&params.query));
+        }
+        return Ok(Json(SongResponse::Hits(hits)));
+    }
 
-            eprintln!("Found {} track IDs: {:?}", track_ids.len(), track_ids);
-
-            // Now get track info for each seokey, just like Python version
-            let mut songs = Vec::new();
-            for track_id in track_ids {
-                let detail_url = format!(
-                    "https://gaana.com/apiv2?type=songDetail&seokey={}",
-                    urlencoding::encode(&track_id)
-                );
-
-                eprintln!("Getting details for track: {}", track_id);
-                match api.make_request_flexible(&detail_url).await {
-                    Ok(detail_response) => {
-                        if let Some(tracks) = detail_response.get("tracks") {
-                            if let Some(tracks_array) = tracks.as_array() {
-                                for track_data in tracks_array {
-                                    let gaana_track: Result<GaanaTrack, _> =
-                                        serde_json::from_value(track_data.clone());
-                                    if let Ok(track) = gaana_track {
-                                        if let Some(song) = api.process_gaana_track(&track) {
-                                            songs.push(song);
-                                        }
-                                    }
+    // Extract track IDs from search response like the Python version does
+    let mut track_ids = Vec::new();
+    let candidates = if query.is_fielded() || fuzzy {
+        filters::overfetch(limit)
+    } else {
+        filters.candidates(limit)
+    };
+
+    // Parse the search response to get seokeys
+    if let Some(gr) = response.get("gr") {
+        if let Some(gr_array) = gr.as_array() {
+            if let Some(first_group) = gr_array.first() {
+                if let Some(gd) = first_group.get("gd") {
+                    if let Some(gd_array) = gd.as_array() {
+                        for (i, item) in gd_array.iter().enumerate() {
+                            if i >= candidates {
+                                break;
+                            }
+                            if let Some(seo) = item.get("seo") {
+                                if let Some(seo_str) = seo.as_str() {
+                                    track_ids.push(seo_str.to_string());
                                 }
                             }
                         }
                     }
-                    Err(e) => {
-                        eprintln!("Failed to get details for track {}: {}", track_id, e);
-                        // Continue with other tracks
-                    }
                 }
             }
+        }
+    }
 
-            if songs.is_empty() {
-                return Err((
-                    StatusCode::NOT_FOUND,
-                    Json(ApiError::not_found("No valid song data found")),
-                ));
+    if fuzzy {
+        // Variant searches are best effort: a failed one just contributes nothing.
+        // Take turns between the original query and each variant so every spelling
+        // gets a share of the candidates instead of only filling what is left over.
+        let variants: Vec<String> = fuzzy::variants(&query.keyword())
+            .into_iter()
+            .skip(1)
+            .collect();
+        let urls: Vec<String> = variants.iter().map(|v| song_search_url(v)).collect();
+        let responses = join_all(urls.iter().map(|url| api.make_request_flexible(url))).await;
+        let mut lists = vec![std::mem::take(&mut track_ids)];
+        for (variant, response) in variants.iter().zip(responses) {
+            match response {
+                Ok(response) => lists.push(
+                    formatting::search_group_items(
+                        formatting::first_search_group(&response),
+                        candidates,
+                    )
+                    .into_iter()
+                    .filter_map(|item| item.get("seo").and_then(|s| s.as_str()))
+                    .map(String::from)
+                    .collect(),
+                ),
+                Err(e) => eprintln!("Variant search '{}' failed: {}", variant, e),
             }
-
-            Ok(Json(SongResponse::Songs(songs)))
         }
-        Err(e) => {
-            eprintln!("Search request error: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ));
+        for seo in formatting::interleave(lists) {
+            if !track_ids.contains(&seo) {
+                track_ids.push(seo);
+            }
         }
+        track_ids.truncate(candidates);
     }
+
+    if track_ids.is_empty() {
+        return Err(ApiError::not_found("No songs found for the given query"));
+    }
+
+    eprintln!("Found {} track IDs: {:?}", track_ids.len(), track_ids);
+
+    // Now get track info for each seokey, just like Python version
+    let mut songs = api
+        .fetch_songs(track_ids)
+        .await
+        .check_strict(params.strict.unwrap_or(false))?;
+    if fuzzy {
+        fuzzy::dedupe_and_rerank(&query.keyword(), &mut songs.results);
+    }
+    query.rank(&mut songs.results);
+    filters.apply(&mut songs.results, limit);
+
+    if songs.is_empty() {
+        if !filters.is_empty() || query.is_fielded() {
+            return Err(ApiError::not_found("No songs match the given filters"));
+        }
+        return Err(ApiError::not_found("No valid song data found"));
+    }
+
+    Ok(Json(SongResponse::Songs(
+        songs.with_missing(include.wants_missing()),
+    )))
+}
+
+fn song_search_url(keyword: &str) -> String {
+    format!(
+        "https://gaana.com/apiv2?country=IN&page=0&secType=track&type=search&keyword={}",
+        urlencoding::encode(keyword)
+    )
 }
 
 /// Get detailed information about a specific song
 #[utoipa::path(
     get,
     path = "/songs/info",
-    params(SongInfoQuery),
+    params(SongInfoQuery, FieldsQuery),
     responses(
         (status = 200, description = "Detailed information about the song", body = Song),
-        (status = 404, description = "Song not found", body = ApiError)
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Song not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "songs"
 )]
 pub async fn get_song_info(
-    Query(params): Query<SongInfoQuery>,
-) -> Result<Json<SongResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<SongInfoQuery>,
+) -> Result<Json<SongResponse>, ApiError> {
     let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Song,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
     let url = format!(
         "https://gaana.com/apiv2?type=songDetail&seokey={}",
-        urlencoding::encode(&params.seokey)
+        urlencoding::encode(&seokey)
     );
 
     eprintln!("Getting song info from: {}", url);
-    match api.make_request_flexible(&url).await {
-        Ok(response) => {
-            eprintln!("Parsing song info response...");
-
-            // Look for 'tracks' array just like in the search function
-            if let Some(tracks) = response.get("tracks") {
-                if let Some(tracks_array) = tracks.as_array() {
-                    if let Some(track_data) = tracks_array.first() {
-                        let gaana_track: Result<GaanaTrack, _> =
-                            serde_json::from_value(track_data.clone());
-                        if let Ok(track) = gaana_track {
-                            if let Some(song) = api.process_gaana_track(&track) {
-                                return Ok(Json(SongResponse::Song(song)));
-                            }
-                        }
+    let response = api.make_request_flexible(&url).await.map_err(|e| {
+        eprintln!("Request failed: {}", e);
+        e
+    })?;
+    eprintln!("Parsing song info response...");
+
+    // Look for 'tracks' array just like in the search function
+    if let Some(tracks) = response.get("tracks") {
+        if let Some(tracks_array) = tracks.as_array() {
+            if let Some(track_data) = tracks_array.first() {
+                let gaana_track: Result<GaanaTrack, _> = serde_json::from_value(track_data.clone());
+                if let Ok(track) = gaana_track {
+                    if let Some(song) = api.process_gaana_track(&track) {
+                        return Ok(Json(SongResponse::Song(Box::new(song))));
                     }
                 }
             }
+        }
+    }
+
+    eprintln!("No valid track found in response");
+    eprintln!(
+        "Response structure: {}",
+        serde_json::to_string_pretty(&response).unwrap_or_default()
+    );
+
+    Err(ApiError::invalid_seokey(&seokey))
+}
+
+/// Get the lyrics of a song, as plain text and time-synced lines
+#[utoipa::path(
+    get,
+    path = "/songs/lyrics",
+    params(SongLyricsQuery, FieldsQuery),
+    responses(
+        (status = 200, description = "Lyrics of the song; `has_lyrics` is false when Gaana has none", body = Lyrics),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Song not found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "songs"
+)]
+pub async fn get_song_lyrics(
+    ValidatedQuery(params): ValidatedQuery<SongLyricsQuery>,
+) -> Result<Json<Lyrics>, ApiError> {
+    let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Song,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
+    let song = api.fetch_song_cached(&seokey).await?;
 
-            eprintln!("No valid track found in response");
-            eprintln!(
-                "Response structure: {}",
-                serde_json::to_string_pretty(&response).unwrap_or_default()
-            );
+    // Skip the lyrics call when the track detail already says there are none
+    let text = match song.has_lyrics {
+        Some(false) => None,
+        _ => api.fetch_lyrics(&song.track_id).await?,
+    };
+    let synced = text.as_deref().map(lyrics::parse_lrc).unwrap_or_default();
+    let plain = text
+        .as_deref()
+        .map(|text| lyrics::to_plain(text, &synced))
+        .filter(|plain| !plain.is_empty());
 
-            Err((
-                StatusCode::NOT_FOUND,
-                Json(ApiError::invalid_seokey(&params.seokey)),
-            ))
+    Ok(Json(Lyrics {
+        track_id: song.track_id,
+        title: song.title,
+        has_lyrics: plain.is_some(),
+        plain,
+        lrc: (!synced.is_empty()).then(|| lyrics::to_lrc(&synced)),
+        synced: (!synced.is_empty()).then_some(synced),
+    }))
+}
+
+/// Get songs related to a given song
+#[utoipa::path(
+    get,
+    path = "/songs/similar",
+    params(SimilarSongsQuery, FieldsQuery, IncludeQuery),
+    responses(
+        (status = 200, description = "Songs related to the given song, from Gaana or ranked by shared artists, album, genres and language; include=missing wraps them as { results, missing }", body = [Song]),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "Song not found or nothing related to it", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
+    ),
+    tag = "songs"
+)]
+pub async fn get_similar_songs(
+    ValidatedQuery(params): ValidatedQuery<SimilarSongsQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+) -> Result<Json<ResultList<Song>>, ApiError> {
+    let api = BaseApi::new();
+    let seokey = api
+        .seokey_or_id(
+            EntityType::Song,
+            params.seokey.as_deref(),
+            params.id.as_deref(),
+        )
+        .await?;
+    let seed = api.fetch_song(&seokey).await?;
+    let limit = params.limit.unwrap_or(10);
+
+    // Gaana has no similar-track data for many songs; fall back to our own heuristics then
+    let similar = match api.fetch_similar_songs(&seed.track_id, limit).await {
+        Ok(similar) if !similar.is_empty() => {
+            similar.check_strict(params.strict.unwrap_or(false))?
         }
-        Err(e) => {
-            eprintln!("Request failed: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ))
+        Ok(_) => heuristic_similar_songs(&api, &seed, limit).await,
+        Err(e)
+            if matches!(
+                e.code,
+                ErrorCode::NotFound | ErrorCode::UpstreamSchemaChanged
+            ) =>
+        {
+            eprintln!("No similar tracks from Gaana: {}", e.message);
+            heuristic_similar_songs(&api, &seed, limit).await
         }
+        Err(e) => return Err(e),
+    };
+
+    if similar.is_empty() {
+        return Err(ApiError::no_results(&format!(
+            "No songs similar to '{}' were found",
+            seokey
+        )));
     }
+
+    Ok(Json(similar.with_missing(include.wants_missing())))
+}
+
+/// Rank songs from the seed's album and artists by what they share with the seed
+async fn heuristic_similar_songs(api: &BaseApi, seed: &Song, limit: usize) -> ResultList<Song> {
+    let candidates = api
+        .fetch_song_candidates(seed, filters::overfetch(limit))
+        .await;
+    ResultList::new(recommend::rank(seed, candidates, limit))
+}
+
+/// Look up many songs at once by seokey or id
+#[utoipa::path(
+    post,
+    path = "/songs/batch",
+    params(FieldsQuery),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Songs keyed by the requested seokey or id, with failures under `missing`", body = SongBatch),
+        (status = 400, description = "Invalid request body", body = ApiError)
+    ),
+    tag = "songs"
+)]
+pub async fn batch_songs(
+    ValidatedJson(request): ValidatedJson<BatchRequest>,
+) -> Json<BatchResponse<Song>> {
+    let api = BaseApi::new();
+    let api = &api;
+    Json(
+        api.fetch_batch(EntityType::Song, request, |seokey| async move {
+            api.fetch_song_cached(&seokey).await
+        })
+        .await,
+    )
 }
@@ -1,111 +1,96 @@
-use axum::{extract::Query, http::StatusCode, response::Json};
+use axum::response::{IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use utoipa::IntoParams;
 
 use crate::api::base::BaseApi;
-use crate::models::{error::ApiError, song::*};
+use crate::models::{error::ApiError, language::Language, results::ResultList, song::*};
+use crate::utils::fields::{FieldsQuery, IncludeQuery};
+use crate::utils::languages::{LanguageQuery, PreferredLanguages};
+use crate::utils::validation::{self, Validate, ValidatedQuery};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct TrendingQuery {
-    #[serde(alias = "lang")]
-    language: Option<String>,
     limit: Option<usize>,
+    /// Fail the whole request if any song cannot be resolved
+    strict: Option<bool>,
+}
+
+impl Validate for TrendingQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.limit = Some(validation::validate_limit(self.limit, 20)?);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum TrendingResponse {
-    Songs(Vec<Song>),
-    Error(ApiError),
+    Songs(ResultList<Song>),
 }
 
 /// Get trending songs by language
 #[utoipa::path(
     get,
     path = "/trending",
-    params(TrendingQuery),
+    params(
+        TrendingQuery,
+        LanguageQuery,
+        ("Accept-Language" = Option<String>, Header, description = "Used when `lang` is not given, e.g. `hi-IN,hi;q=0.9`"),
+        FieldsQuery,
+        IncludeQuery
+    ),
     responses(
-        (status = 200, description = "List of trending songs", body = Vec<Song>),
-        (status = 404, description = "No trending songs found", body = ApiError)
+        (status = 200, description = "List of trending songs; include=missing wraps them as { results, missing }", body = [Song], headers(
+            ("Content-Language" = String, description = "ISO codes of the languages the response was built for"),
+            ("X-Language-Source" = String, description = "`query`, `accept-language` or `default`")
+        )),
+        (status = 400, description = "Invalid query parameters", body = ApiError),
+        (status = 404, description = "No trending songs found", body = ApiError),
+        (status = 502, description = "Unexpected response from Gaana", body = ApiError),
+        (status = 503, description = "Gaana is unavailable", body = ApiError),
+        (status = 504, description = "Gaana timed out", body = ApiError)
     ),
     tag = "trending"
 )]
 pub async fn get_trending(
-    Query(params): Query<TrendingQuery>,
-) -> Result<Json<TrendingResponse>, (StatusCode, Json<ApiError>)> {
+    ValidatedQuery(params): ValidatedQuery<TrendingQuery>,
+    ValidatedQuery(include): ValidatedQuery<IncludeQuery>,
+    preferred: PreferredLanguages,
+) -> Result<impl IntoResponse, ApiError> {
     let api = BaseApi::new();
 
     eprintln!("Trending request params: {:?}", params);
 
-    // Use the language as-is, just like the Python version
-    let language = params.language.unwrap_or_else(|| "English".to_string());
+    // Gaana's language cookie takes several comma-separated languages
+    let chosen = preferred.resolve(Language::English);
     let limit = params.limit.unwrap_or(20);
 
     // Use the exact same approach as the working Python version
     let url = "https://gaana.com/apiv2?type=miscTrendingSongs";
     eprintln!("Trying a request to: {}", url);
 
-    let client = reqwest::Client::new();
-
     // POST request with simple cookie like Python version
-    let response = client
-        .post(url)
-        .header("Cookie", format!("__ul={}", language))
-        .header("Content-Length", "0")
-        .body("")
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) => {
-            if !resp.status().is_success() {
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::internal_error(&format!(
-                        "HTTP request failed with status: {}",
-                        resp.status()
-                    ))),
-                ));
-            }
-
-            let response_text = resp.text().await.map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiError::internal_error(&format!(
-                        "Failed to read response: {}",
-                        e
-                    ))),
-                )
-            })?;
-
-            let json_response: serde_json::Value =
-                serde_json::from_str(&response_text).map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiError::internal_error(&format!(
-                            "Failed to parse JSON: {}",
-                            e
-                        ))),
-                    )
-                })?;
-
-            process_trending_response(json_response, limit, api).await
-        }
-        Err(e) => {
+    let json_response = api
+        .make_request_with_language(url, &chosen.languages.join())
+        .await
+        .map_err(|e| {
             eprintln!("Trending request error: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiError::internal_error(&format!("Request failed: {}", e))),
-            ))
-        }
-    }
+            e
+        })?;
+
+    let songs =
+        process_trending_response(json_response, limit, params.strict.unwrap_or(false), api)
+            .await?
+            .with_missing(include.wants_missing());
+    Ok((chosen.headers(), Json(TrendingResponse::Songs(songs))))
 }
 
 async fn process_trending_response(
     json_response: serde_json::Value,
     limit: usize,
+    strict: bool,
     api: BaseApi,
-) -> Result<Json<TrendingResponse>, (StatusCode, Json<ApiError>)> {
+) -> Result<ResultList<Song>, ApiError> {
     eprintln!("Got trending response, extracting track seokeys...");
 
     // Extract track seokeys from entities array like the Python version
@@ -127,10 +112,7 @@ async fn process_trending_response(
     }
 
     if track_seokeys.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiError::not_found("No trending songs found")),
-        ));
+        return Err(ApiError::not_found("No trending songs found"));
     }
 
     eprintln!(
@@ -140,42 +122,11 @@ async fn process_trending_response(
     );
 
     // Now get track info for each seokey
-    let mut songs = Vec::new();
-    for track_id in track_seokeys {
-        let detail_url = format!(
-            "https://gaana.com/apiv2?type=songDetail&seokey={}",
-            urlencoding::encode(&track_id)
-        );
-
-        match api.make_request_flexible(&detail_url).await {
-            Ok(detail_response) => {
-                if let Some(tracks) = detail_response.get("tracks") {
-                    if let Some(tracks_array) = tracks.as_array() {
-                        for track_data in tracks_array {
-                            let gaana_track: Result<GaanaTrack, _> =
-                                serde_json::from_value(track_data.clone());
-                            if let Ok(track) = gaana_track {
-                                if let Some(song) = api.process_gaana_track(&track) {
-                                    songs.push(song);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to get details for track {}: {}", track_id, e);
-                // Continue with other tracks
-            }
-        }
-    }
+    let songs = api.fetch_songs(track_seokeys).await.check_strict(strict)?;
 
     if songs.is_empty() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiError::not_found("No valid trending song data found")),
-        ));
+        return Err(ApiError::not_found("No valid trending song data found"));
     }
 
-    Ok(Json(TrendingResponse::Songs(songs)))
+    Ok(songs)
 }
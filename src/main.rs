@@ -1,8 +1,15 @@
-use axum::{http::Method, response::Json, routing::get, Router};
+use axum::{
+    http::{HeaderName, Method},
+    middleware::{from_fn, from_fn_with_state},
+    response::Json,
+    routing::{get, post},
+    Router,
+};
 use serde_json::{json, Value};
 use std::env;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
@@ -19,25 +26,66 @@ use api::*;
     paths(
         api::songs::search_songs,
         api::songs::get_song_info,
+        api::songs::get_song_lyrics,
+        api::songs::get_similar_songs,
+        api::songs::batch_songs,
         api::albums::search_albums,
         api::albums::get_album_info,
+        api::albums::batch_albums,
         api::artists::search_artists,
         api::artists::get_artist_info,
+        api::artists::batch_artists,
+        api::artists::get_artist_albums,
+        api::artists::get_similar_artists,
+        api::artists::get_artist_graph,
+        api::playlists::search_playlists,
         api::playlists::get_playlist_info,
+        api::search::search,
+        api::search::suggest,
+        api::resolve::resolve,
+        api::radio::get_radio,
+        api::genres::list_genres,
+        api::genres::get_genre_songs,
+        api::genres::get_genre_playlists,
+        api::languages::list_languages,
         api::trending::get_trending,
         api::newreleases::get_new_releases,
         api::charts::get_charts,
     ),
     components(
-        schemas(models::song::Song, models::album::Album, models::artist::Artist, 
-                models::playlist::Playlist, models::error::ApiError, models::images::Images,
-                models::stream_urls::StreamUrls)
+        schemas(models::song::Song, models::album::Album, models::artist::Artist,
+                models::playlist::Playlist, models::error::ApiError, models::error::ErrorCode,
+                models::error::ProblemDetails,
+                models::images::Images,
+                models::stream_urls::StreamUrls, models::results::MissingItem,
+                models::results::SongList, models::results::AlbumList,
+                models::results::ArtistList, models::results::PlaylistList,
+                models::results::AlbumPage, models::results::SongPage,
+                models::artist::ArtistGraph, models::artist::ArtistNode,
+                models::artist::ArtistEdge, api::newreleases::NewReleasesResponse,
+                api::search::SearchResponse, api::search::SearchHitsResponse,
+                api::search::SuggestResponse, models::search::Suggestion,
+                models::search::SearchHit, models::results::SearchHitList,
+                models::search::EntityType, api::resolve::ResolveResponse,
+                api::resolve::ResolvedEntity, models::batch::BatchRequest,
+                models::batch::SongBatch, models::batch::AlbumBatch,
+                models::batch::ArtistBatch, models::radio::RadioResponse,
+                models::radio::RadioSeed, models::radio::RadioSeedType,
+                models::lyrics::Lyrics, models::lyrics::LyricLine,
+                models::genre::Genre, models::genre::GenreKind,
+                models::results::PlaylistPage, models::language::LanguageInfo,
+                models::language::Language)
     ),
     tags(
         (name = "songs", description = "Song search and information endpoints"),
         (name = "albums", description = "Album search and information endpoints"),
         (name = "artists", description = "Artist search and information endpoints"),
-        (name = "playlists", description = "Playlist information endpoints"),
+        (name = "playlists", description = "Playlist search and information endpoints"),
+        (name = "search", description = "Multi-entity search endpoints"),
+        (name = "resolve", description = "Gaana link resolution endpoints"),
+        (name = "radio", description = "Endless radio station endpoints"),
+        (name = "genres", description = "Genre and mood browsing endpoints"),
+        (name = "languages", description = "Supported language endpoints"),
         (name = "trending", description = "Trending content endpoints"),
         (name = "newreleases", description = "New releases endpoints"),
         (name = "charts", description = "Charts endpoints")
@@ -54,19 +102,40 @@ async fn root() -> Json<Value> {
         "endpoints": {
             "songs": {
                 "search": "/songs/search",
-                "info": "/songs/info"
+                "info": "/songs/info",
+                "lyrics": "/songs/lyrics",
+                "similar": "/songs/similar",
+                "batch": "/songs/batch"
             },
             "albums": {
                 "search": "/albums/search",
-                "info": "/albums/info"
+                "info": "/albums/info",
+                "batch": "/albums/batch"
             },
             "artists": {
                 "search": "/artists/search",
-                "info": "/artists/info"
+                "info": "/artists/info",
+                "batch": "/artists/batch",
+                "albums": "/artists/albums",
+                "similar": "/artists/similar",
+                "graph": "/artists/graph"
             },
             "playlists": {
+                "search": "/playlists/search",
                 "info": "/playlists/info"
             },
+            "search": {
+                "all": "/search",
+                "suggest": "/search/suggest"
+            },
+            "resolve": "/resolve",
+            "languages": "/languages",
+            "radio": "/radio",
+            "genres": {
+                "list": "/genres",
+                "songs": "/genres/{id}/songs",
+                "playlists": "/genres/{id}/playlists"
+            },
             "trending": "/trending",
             "newreleases": "/newreleases",
             "charts": "/charts"
@@ -94,6 +163,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse::<u16>()
         .unwrap_or(8000);
 
+    // Error body format: "json" (default) or "problem" for RFC 7807 problem+json
+    let error_format = env::var("ERROR_FORMAT")
+        .map(|v| middleware::ErrorFormat::from_env_value(&v))
+        .unwrap_or(middleware::ErrorFormat::Json);
+    let request_id_header = HeaderName::from_static(middleware::REQUEST_ID_HEADER);
+
     // Build our application with routes
     let app = Router::new()
         .route("/", get(root))
@@ -102,19 +177,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/songs/search/", get(songs::search_songs))
         .route("/songs/info", get(songs::get_song_info))
         .route("/songs/info/", get(songs::get_song_info))
+        .route("/songs/lyrics", get(songs::get_song_lyrics))
+        .route("/songs/lyrics/", get(songs::get_song_lyrics))
+        .route("/songs/similar", get(songs::get_similar_songs))
+        .route("/songs/similar/", get(songs::get_similar_songs))
+        .route("/songs/batch", post(songs::batch_songs))
+        .route("/songs/batch/", post(songs::batch_songs))
         // Album endpoints
         .route("/albums/search", get(albums::search_albums))
         .route("/albums/search/", get(albums::search_albums))
         .route("/albums/info", get(albums::get_album_info))
         .route("/albums/info/", get(albums::get_album_info))
+        .route("/albums/batch", post(albums::batch_albums))
+        .route("/albums/batch/", post(albums::batch_albums))
         // Artist endpoints
         .route("/artists/search", get(artists::search_artists))
         .route("/artists/search/", get(artists::search_artists))
         .route("/artists/info", get(artists::get_artist_info))
         .route("/artists/info/", get(artists::get_artist_info))
+        .route("/artists/batch", post(artists::batch_artists))
+        .route("/artists/batch/", post(artists::batch_artists))
+        .route("/artists/albums", get(artists::get_artist_albums))
+        .route("/artists/albums/", get(artists::get_artist_albums))
+        .route("/artists/similar", get(artists::get_similar_artists))
+        .route("/artists/similar/", get(artists::get_similar_artists))
+        .route("/artists/graph", get(artists::get_artist_graph))
+        .route("/artists/graph/", get(artists::get_artist_graph))
         // Playlist endpoints
+        .route("/playlists/search", get(playlists::search_playlists))
+        .route("/playlists/search/", get(playlists::search_playlists))
         .route("/playlists/info", get(playlists::get_playlist_info))
         .route("/playlists/info/", get(playlists::get_playlist_info))
+        // Unified search
+        .route("/search", get(search::search))
+        .route("/search/", get(search::search))
+        .route("/search/suggest", get(search::suggest))
+        .route("/search/suggest/", get(search::suggest))
+        // Link resolution
+        .route("/resolve", get(resolve::resolve))
+        .route("/resolve/", get(resolve::resolve))
+        // Radio stations
+        .route("/radio", get(radio::get_radio))
+        .route("/radio/", get(radio::get_radio))
+        // Supported languages
+        .route("/languages", get(languages::list_languages))
+        .route("/languages/", get(languages::list_languages))
+        // Genre and mood browsing
+        .route("/genres", get(genres::list_genres))
+        .route("/genres/", get(genres::list_genres))
+        .route("/genres/:id/songs", get(genres::get_genre_songs))
+        .route("/genres/:id/songs/", get(genres::get_genre_songs))
+        .route("/genres/:id/playlists", get(genres::get_genre_playlists))
+        .route("/genres/:id/playlists/", get(genres::get_genre_playlists))
         // Trending, New Releases, Charts
         .route("/trending", get(trending::get_trending))
         .route("/trending/", get(trending::get_trending))
@@ -124,9 +238,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/charts/", get(charts::get_charts))
         // Swagger UI
         .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .layer(from_fn(middleware::sparse_fieldsets))
+        .layer(from_fn_with_state(
+            error_format,
+            middleware::problem_details,
+        ))
         .layer(
             ServiceBuilder::new()
+                .layer(SetRequestIdLayer::new(
+                    request_id_header.clone(),
+                    MakeRequestUuid,
+                ))
                 .layer(TraceLayer::new_for_http())
+                .layer(PropagateRequestIdLayer::new(request_id_header))
                 .layer(
                     CorsLayer::new()
                         .allow_origin(Any)
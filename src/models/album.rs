@@ -21,6 +21,7 @@ pub struct Album {
     pub tracks: Option<Vec<crate::models::song::Song>>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GaanaAlbumResponse {
     pub albums: Option<Vec<GaanaAlbum>>,
@@ -12,9 +12,36 @@ pub struct Artist {
     pub favorite_count: Option<i32>,
     pub artist_url: String,
     pub images: Option<Images>,
-    pub top_tracks: Option<Vec<crate::models::song::Song>>,
+    /// With `include=top_tracks`; `include=top_tracks,missing` also lists tracks that could not be resolved
+    #[schema(value_type = Option<Vec<crate::models::song::Song>>)]
+    pub top_tracks: Option<crate::models::results::ResultList<crate::models::song::Song>>,
 }
 
+/// An artist in a relationship graph, with its distance from the seed artist
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ArtistNode {
+    #[serde(flatten)]
+    pub artist: Artist,
+    pub depth: usize,
+}
+
+/// A "similar to" relationship between two artists, by seokey
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, ToSchema)]
+pub struct ArtistEdge {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ArtistGraph {
+    pub nodes: Vec<ArtistNode>,
+    pub edges: Vec<ArtistEdge>,
+    /// Only with `include=missing`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<crate::models::results::MissingItem>>,
+}
+
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GaanaArtistResponse {
     pub artists: Option<Vec<GaanaArtist>>,
@@ -22,6 +49,7 @@ pub struct GaanaArtistResponse {
     pub tracks: Option<Vec<crate::models::song::GaanaTrack>>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GaanaArtist {
     pub seokey: Option<String>,
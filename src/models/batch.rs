@@ -0,0 +1,22 @@
+use crate::models::{album::Album, artist::Artist, results::MissingItem, song::Song};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Body of the `/batch` endpoints: seokeys, numeric ids, or both
+#[derive(Debug, Default, Serialize, Deserialize, Clone, ToSchema)]
+pub struct BatchRequest {
+    #[serde(default)]
+    pub seokeys: Vec<String>,
+    #[serde(default)]
+    pub ids: Vec<String>,
+}
+
+/// Resolved entities keyed by the seokey or id they were requested with;
+/// inputs that failed are listed under `missing` with the same key
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[aliases(SongBatch = BatchResponse<Song>, AlbumBatch = BatchResponse<Album>, ArtistBatch = BatchResponse<Artist>)]
+pub struct BatchResponse<T> {
+    pub results: BTreeMap<String, T>,
+    pub missing: Vec<MissingItem>,
+}
@@ -1,39 +1,228 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+/// Stable, machine-readable error codes returned with every error response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    InvalidParameter,
+    RateLimited,
+    UpstreamUnavailable,
+    UpstreamTimeout,
+    UpstreamSchemaChanged,
+    InternalError,
+}
+
+impl ErrorCode {
+    /// HTTP status used when an error with this code is returned to the client
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::InvalidParameter => StatusCode::BAD_REQUEST,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::UpstreamSchemaChanged => StatusCode::BAD_GATEWAY,
+            ErrorCode::UpstreamUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::UpstreamTimeout => StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Short, human-readable summary shared by every problem of this type
+    pub fn title(self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "Resource not found",
+            ErrorCode::InvalidParameter => "Invalid request parameter",
+            ErrorCode::RateLimited => "Rate limited by upstream",
+            ErrorCode::UpstreamUnavailable => "Upstream service unavailable",
+            ErrorCode::UpstreamTimeout => "Upstream service timed out",
+            ErrorCode::UpstreamSchemaChanged => "Unexpected upstream response",
+            ErrorCode::InternalError => "Internal server error",
+        }
+    }
+
+    /// Problem type URI, relative to the API root
+    pub fn type_uri(self) -> String {
+        let slug = match self {
+            ErrorCode::NotFound => "not-found",
+            ErrorCode::InvalidParameter => "invalid-parameter",
+            ErrorCode::RateLimited => "rate-limited",
+            ErrorCode::UpstreamUnavailable => "upstream-unavailable",
+            ErrorCode::UpstreamTimeout => "upstream-timeout",
+            ErrorCode::UpstreamSchemaChanged => "upstream-schema-changed",
+            ErrorCode::InternalError => "internal-error",
+        };
+        format!("/problems/{}", slug)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, thiserror::Error)]
+#[error("{error}: {message}")]
 pub struct ApiError {
+    pub code: ErrorCode,
     pub error: String,
     pub message: String,
 }
 
 impl ApiError {
-    pub fn new(error: &str, message: &str) -> Self {
+    pub fn new(code: ErrorCode, error: &str, message: &str) -> Self {
         Self {
+            code,
             error: error.to_string(),
             message: message.to_string(),
         }
     }
 
+    pub fn status(&self) -> StatusCode {
+        self.code.status()
+    }
+
     pub fn not_found(query: &str) -> Self {
         Self::new(
+            ErrorCode::NotFound,
             "No results found",
             &format!("No search results for the given query: {}", query),
         )
     }
 
+    /// A lookup that worked but had nothing to return, e.g. no similar artists
+    pub fn no_results(message: &str) -> Self {
+        Self::new(ErrorCode::NotFound, "No results found", message)
+    }
+
     pub fn invalid_seokey(seokey: &str) -> Self {
         Self::new(
+            ErrorCode::NotFound,
             "Invalid seokey",
             &format!("The provided seokey is invalid or not found: {}", seokey),
         )
     }
 
+    pub fn invalid_id(id: &str) -> Self {
+        Self::new(
+            ErrorCode::NotFound,
+            "Invalid id",
+            &format!("The provided id is invalid or not found: {}", id),
+        )
+    }
+
+    pub fn unknown_genre(id: &str) -> Self {
+        Self::new(
+            ErrorCode::NotFound,
+            "Unknown genre",
+            &format!("No genre or mood with id '{}'; see /genres", id),
+        )
+    }
+
     pub fn internal_error(message: &str) -> Self {
-        Self::new("Internal server error", message)
+        Self::new(ErrorCode::InternalError, "Internal server error", message)
     }
 
     pub fn invalid_parameter(param: &str, message: &str) -> Self {
-        Self::new(&format!("Invalid parameter: {}", param), message)
+        Self::new(
+            ErrorCode::InvalidParameter,
+            &format!("Invalid parameter: {}", param),
+            message,
+        )
+    }
+
+    pub fn rate_limited(message: &str) -> Self {
+        Self::new(ErrorCode::RateLimited, "Rate limited", message)
+    }
+
+    pub fn upstream_unavailable(message: &str) -> Self {
+        Self::new(
+            ErrorCode::UpstreamUnavailable,
+            "Upstream unavailable",
+            message,
+        )
+    }
+
+    pub fn upstream_timeout(message: &str) -> Self {
+        Self::new(ErrorCode::UpstreamTimeout, "Upstream timeout", message)
+    }
+
+    pub fn upstream_schema_changed(message: &str) -> Self {
+        Self::new(
+            ErrorCode::UpstreamSchemaChanged,
+            "Upstream schema changed",
+            message,
+        )
+    }
+
+    /// Map a non-success status returned by Gaana to an error
+    pub fn from_upstream_status(status: reqwest::StatusCode) -> Self {
+        let message = format!("HTTP request failed with status: {}", status);
+        match status.as_u16() {
+            429 => Self::rate_limited(&message),
+            408 | 504 => Self::upstream_timeout(&message),
+            _ => Self::upstream_unavailable(&message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::upstream_timeout(&format!("Request timed out: {}", e))
+        } else if e.is_decode() {
+            Self::upstream_schema_changed(&format!("Failed to decode response: {}", e))
+        } else if let Some(status) = e.status() {
+            Self::from_upstream_status(status)
+        } else {
+            Self::upstream_unavailable(&format!("Request failed: {}", e))
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status(), Json(self.clone())).into_response();
+        // Keep the typed error around so middleware can re-render it as problem details
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// RFC 7807 problem details representation of an `ApiError`
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub instance: String,
+    pub code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ProblemDetails {
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    pub fn from_error(error: &ApiError, instance: &str, request_id: Option<String>) -> Self {
+        Self {
+            problem_type: error.code.type_uri(),
+            title: error.code.title().to_string(),
+            status: error.status().as_u16(),
+            detail: error.message.clone(),
+            instance: instance.to_string(),
+            code: error.code,
+            request_id,
+        }
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let body = serde_json::to_vec(&self).unwrap_or_default();
+        (status, [(header::CONTENT_TYPE, Self::CONTENT_TYPE)], body).into_response()
     }
 }
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::language::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GenreKind {
+    Genre,
+    Mood,
+}
+
+/// A genre or mood that can be browsed with `/genres/{id}/songs` and `/genres/{id}/playlists`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Genre {
+    pub id: String,
+    pub name: String,
+    pub kind: GenreKind,
+    /// Languages the genre is found in; empty when it spans all of them
+    pub languages: Vec<Language>,
+    pub songs_url: String,
+    pub playlists_url: String,
+}
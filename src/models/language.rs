@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+/// A language Gaana serves content for, serialized by its Gaana name (`Hindi`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+pub enum Language {
+    English,
+    Hindi,
+    Punjabi,
+    Telugu,
+    Tamil,
+    Bengali,
+    Gujarati,
+    Kannada,
+    Malayalam,
+    Marathi,
+    Odia,
+    Assamese,
+}
+
+impl Language {
+    pub const ALL: [Language; 12] = [
+        Language::English,
+        Language::Hindi,
+        Language::Punjabi,
+        Language::Telugu,
+        Language::Tamil,
+        Language::Bengali,
+        Language::Gujarati,
+        Language::Kannada,
+        Language::Malayalam,
+        Language::Marathi,
+        Language::Odia,
+        Language::Assamese,
+    ];
+
+    /// Name Gaana uses in its `language` parameters and fields
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Hindi => "Hindi",
+            Language::Punjabi => "Punjabi",
+            Language::Telugu => "Telugu",
+            Language::Tamil => "Tamil",
+            Language::Bengali => "Bengali",
+            Language::Gujarati => "Gujarati",
+            Language::Kannada => "Kannada",
+            Language::Malayalam => "Malayalam",
+            Language::Marathi => "Marathi",
+            Language::Odia => "Odia",
+            Language::Assamese => "Assamese",
+        }
+    }
+
+    /// Name in the language's own script
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Hindi => "हिन्दी",
+            Language::Punjabi => "ਪੰਜਾਬੀ",
+            Language::Telugu => "తెలుగు",
+            Language::Tamil => "தமிழ்",
+            Language::Bengali => "বাংলা",
+            Language::Gujarati => "ગુજરાતી",
+            Language::Kannada => "ಕನ್ನಡ",
+            Language::Malayalam => "മലയാളം",
+            Language::Marathi => "मराठी",
+            Language::Odia => "ଓଡ଼ିଆ",
+            Language::Assamese => "অসমীয়া",
+        }
+    }
+
+    /// ISO 639-1 code
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Hindi => "hi",
+            Language::Punjabi => "pa",
+            Language::Telugu => "te",
+            Language::Tamil => "ta",
+            Language::Bengali => "bn",
+            Language::Gujarati => "gu",
+            Language::Kannada => "kn",
+            Language::Malayalam => "ml",
+            Language::Marathi => "mr",
+            Language::Odia => "or",
+            Language::Assamese => "as",
+        }
+    }
+
+    /// Whether a language name reported by Gaana is this language
+    pub fn matches(self, name: &str) -> bool {
+        self.name().eq_ignore_ascii_case(name.trim())
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One or more distinct languages, written `Hindi,Punjabi` in query strings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Languages(Vec<Language>);
+
+impl Languages {
+    pub fn as_slice(&self) -> &[Language] {
+        &self.0
+    }
+
+    /// The only language, if exactly one was given
+    pub fn single(&self) -> Option<Language> {
+        match self.0.as_slice() {
+            [language] => Some(*language),
+            _ => None,
+        }
+    }
+
+    /// Whether a language name reported by Gaana is one of these languages
+    pub fn matches(&self, name: &str) -> bool {
+        self.0.iter().any(|l| l.matches(name))
+    }
+
+    /// Gaana names joined with commas, as Gaana's language cookie expects
+    pub fn join(&self) -> String {
+        self.0
+            .iter()
+            .map(|l| l.name())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl From<Language> for Languages {
+    fn from(language: Language) -> Self {
+        Self(vec![language])
+    }
+}
+
+/// Collects distinct languages, keeping the first occurrence of each
+impl FromIterator<Language> for Languages {
+    fn from_iter<I: IntoIterator<Item = Language>>(iter: I) -> Self {
+        let mut languages = Vec::new();
+        for language in iter {
+            if !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+        Self(languages)
+    }
+}
+
+/// A supported language as listed by `/languages`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LanguageInfo {
+    /// Name accepted by `lang` and used in Gaana's `language` fields
+    pub name: String,
+    /// Name in the language's own script
+    pub native_name: String,
+    /// ISO 639-1 code, also accepted by `lang`
+    pub code: String,
+}
+
+impl From<Language> for LanguageInfo {
+    fn from(language: Language) -> Self {
+        Self {
+            name: language.name().to_string(),
+            native_name: language.native_name().to_string(),
+            code: language.code().to_string(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One timed line of synced lyrics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LyricLine {
+    /// Offset from the start of the track in milliseconds
+    pub time_ms: u64,
+    pub text: String,
+}
+
+/// Lyrics of a song; every variant is `null` when `has_lyrics` is false
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Lyrics {
+    pub track_id: String,
+    pub title: String,
+    pub has_lyrics: bool,
+    /// Lyrics as plain text, one line per line
+    pub plain: Option<String>,
+    /// Timed lines, when Gaana has synced lyrics
+    pub synced: Option<Vec<LyricLine>>,
+    /// `synced` rendered as an LRC file
+    pub lrc: Option<String>,
+}
@@ -1,7 +1,14 @@
 pub mod album;
 pub mod artist;
+pub mod batch;
 pub mod error;
+pub mod genre;
 pub mod images;
+pub mod language;
+pub mod lyrics;
 pub mod playlist;
+pub mod radio;
+pub mod results;
+pub mod search;
 pub mod song;
 pub mod stream_urls;
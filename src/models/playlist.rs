@@ -1,4 +1,4 @@
-use crate::models::images::Images;
+use crate::models::{images::Images, results::Paginated, song::Song};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -14,8 +14,12 @@ pub struct Playlist {
     pub playlist_url: String,
     pub images: Option<Images>,
     pub tracks_url: String,
+    pub total_tracks: Option<i32>,
+    #[schema(value_type = Option<SongPage>)]
+    pub tracks: Option<Paginated<Song>>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GaanaPlaylistResponse {
     pub playlist: Option<GaanaPlaylist>,
@@ -0,0 +1,94 @@
+use crate::models::{error::ApiError, song::Song};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most played track ids a radio token remembers to avoid repeats
+pub const MAX_RADIO_HISTORY: usize = 200;
+
+/// Latest songs of a batch expanded for the next one
+pub const FRONTIER_SIZE: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RadioSeedType {
+    Song,
+    Artist,
+    Genre,
+}
+
+/// What a radio station was started from: a song or artist seokey, or a genre name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RadioSeed {
+    #[serde(rename = "type")]
+    pub seed_type: RadioSeedType,
+    pub value: String,
+}
+
+/// State carried between radio batches in the opaque continuation token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RadioState {
+    pub seed: RadioSeed,
+    /// Track ids already returned, oldest first
+    pub played: Vec<String>,
+    /// Seokeys of the latest songs, expanded for the next batch
+    pub frontier: Vec<String>,
+    /// Primary artist of the last song, so the next batch does not open with it
+    pub last_artist: Option<String>,
+}
+
+impl RadioState {
+    pub fn new(seed: RadioSeed) -> Self {
+        Self {
+            seed,
+            played: Vec::new(),
+            frontier: Vec::new(),
+            last_artist: None,
+        }
+    }
+
+    /// Record a batch as played, forgetting the oldest ids past `MAX_RADIO_HISTORY`
+    pub fn remember<'a>(&mut self, track_ids: impl IntoIterator<Item = &'a String>) {
+        self.played.extend(track_ids.into_iter().cloned());
+        let overflow = self.played.len().saturating_sub(MAX_RADIO_HISTORY);
+        self.played.drain(..overflow);
+    }
+
+    /// Start over from the seed, still avoiding the latest `keep` played tracks
+    pub fn restart(&mut self, keep: usize) {
+        let forget = self.played.len().saturating_sub(keep);
+        self.played.drain(..forget);
+        self.frontier.clear();
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        general_purpose::URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Decode a token, rejecting more history than `encode` ever writes; the seed and
+    /// frontier are checked by the caller, since clients can edit them
+    pub fn decode(token: &str) -> Result<Self, ApiError> {
+        let state: Self = general_purpose::URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| ApiError::invalid_parameter("token", "Malformed radio token"))?;
+
+        if state.frontier.len() > FRONTIER_SIZE || state.played.len() > MAX_RADIO_HISTORY {
+            return Err(ApiError::invalid_parameter(
+                "token",
+                "Radio token carries more history than this server hands out",
+            ));
+        }
+        Ok(state)
+    }
+}
+
+/// One batch of a radio station; pass `next_token` back as `token` for the next batch
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RadioResponse {
+    pub seed: RadioSeed,
+    pub results: Vec<Song>,
+    pub next_token: String,
+}
@@ -0,0 +1,117 @@
+use crate::models::{
+    album::Album,
+    artist::Artist,
+    error::{ApiError, ErrorCode},
+    playlist::Playlist,
+    search::SearchHit,
+    song::Song,
+};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use utoipa::ToSchema;
+
+/// An entity that could not be resolved while fanning out detail requests
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct MissingItem {
+    pub seokey: String,
+    pub code: ErrorCode,
+    pub reason: String,
+}
+
+impl MissingItem {
+    pub fn new(seokey: &str, error: &ApiError) -> Self {
+        Self {
+            seokey: seokey.to_string(),
+            code: error.code,
+            reason: error.message.clone(),
+        }
+    }
+}
+
+/// Entities resolved by a fan-out, plus the ones that could not be. Serialized as a bare
+/// array of `results`, or as `{ results, missing }` once `with_missing(true)` is set.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+#[aliases(SongList = ResultList<Song>, AlbumList = ResultList<Album>, ArtistList = ResultList<Artist>, PlaylistList = ResultList<Playlist>, SearchHitList = ResultList<SearchHit>)]
+pub struct ResultList<T> {
+    pub results: Vec<T>,
+    pub missing: Vec<MissingItem>,
+    #[serde(skip)]
+    pub include_missing: bool,
+}
+
+impl<T: Serialize> Serialize for ResultList<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.include_missing {
+            return self.results.serialize(serializer);
+        }
+        let mut state = serializer.serialize_struct("ResultList", 2)?;
+        state.serialize_field("results", &self.results)?;
+        state.serialize_field("missing", &self.missing)?;
+        state.end()
+    }
+}
+
+impl<T> ResultList<T> {
+    pub fn new(results: Vec<T>) -> Self {
+        Self {
+            results,
+            missing: Vec::new(),
+            include_missing: false,
+        }
+    }
+
+    /// Report `missing` alongside the results, for `include=missing`
+    pub fn with_missing(mut self, include: bool) -> Self {
+        self.include_missing = include;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// With `strict`, turn any missing item into a failure of the whole request
+    pub fn check_strict(self, strict: bool) -> Result<Self, ApiError> {
+        match self.missing.first() {
+            Some(first) if strict => Err(ApiError::new(
+                first.code,
+                "Incomplete results",
+                &format!(
+                    "{} item(s) could not be resolved: {}",
+                    self.missing.len(),
+                    self.missing
+                        .iter()
+                        .map(|m| format!("{} ({})", m.seokey, m.reason))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+            _ => Ok(self),
+        }
+    }
+}
+
+/// One page of a paginated listing
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[aliases(AlbumPage = Paginated<Album>, SongPage = Paginated<Song>, PlaylistPage = Paginated<Playlist>)]
+pub struct Paginated<T> {
+    pub results: Vec<T>,
+    /// Only with `include=missing`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing: Option<Vec<MissingItem>>,
+    pub page: usize,
+    pub limit: usize,
+    /// Page to request next, if there may be more results
+    pub next_page: Option<usize>,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(list: ResultList<T>, page: usize, limit: usize, has_more: bool) -> Self {
+        Self {
+            results: list.results,
+            missing: list.include_missing.then_some(list.missing),
+            page,
+            limit,
+            next_page: has_more.then_some(page + 1),
+        }
+    }
+}
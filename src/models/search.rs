@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Kind of Gaana entity a search hit or URL refers to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityType {
+    Song,
+    Album,
+    Artist,
+    Playlist,
+}
+
+impl EntityType {
+    /// Map a search group type (`ty`) such as `Track` or `Album`
+    pub fn from_group_type(ty: &str) -> Option<Self> {
+        match ty.to_ascii_lowercase().as_str() {
+            "track" | "song" => Some(EntityType::Song),
+            "album" => Some(EntityType::Album),
+            "artist" => Some(EntityType::Artist),
+            "playlist" => Some(EntityType::Playlist),
+            _ => None,
+        }
+    }
+}
+
+/// How much a search resolves about each hit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchDetail {
+    /// Title, type, seokey and thumbnail from the search response only
+    None,
+    /// Everything the search response carries for the hit, still without detail calls
+    Basic,
+    /// One detail call per hit, returning full entities
+    #[default]
+    Full,
+}
+
+/// Lightweight search hit built straight from search results, without detail calls
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Suggestion {
+    pub title: String,
+    pub entity_type: EntityType,
+    pub seokey: String,
+    pub thumbnail: Option<String>,
+}
+
+impl Suggestion {
+    /// Build from a search entry (`gd` item); entries without a title or seokey are skipped
+    pub fn from_search_item(item: &Value, entity_type: EntityType) -> Option<Self> {
+        Some(Self {
+            title: item.get("ti")?.as_str()?.to_string(),
+            entity_type,
+            seokey: item.get("seo")?.as_str()?.to_string(),
+            thumbnail: string_field(item, "aw"),
+        })
+    }
+}
+
+/// Search hit returned by `detail=none` and `detail=basic`
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub suggestion: Suggestion,
+    /// Secondary line shown by Gaana, usually artists or album
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl SearchHit {
+    pub fn from_search_item(
+        item: &Value,
+        entity_type: EntityType,
+        detail: SearchDetail,
+    ) -> Option<Self> {
+        let suggestion = Suggestion::from_search_item(item, entity_type)?;
+        if detail == SearchDetail::None {
+            return Some(Self {
+                suggestion,
+                subtitle: None,
+                entity_id: None,
+                language: None,
+            });
+        }
+        Some(Self {
+            suggestion,
+            subtitle: string_field(item, "sti"),
+            entity_id: item.get("id").and_then(|id| match id {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }),
+            language: string_field(item, "language"),
+        })
+    }
+}
+
+fn string_field(item: &Value, key: &str) -> Option<String> {
+    item.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
@@ -18,6 +18,8 @@ pub struct Song {
     pub popularity: Option<String>,
     pub genres: Option<String>,
     pub is_explicit: Option<i32>,
+    /// Whether Gaana has lyrics for the song; `null` when it does not say
+    pub has_lyrics: Option<bool>,
     pub language: Option<String>,
     pub label: Option<String>,
     pub release_date: Option<String>,
@@ -29,6 +31,7 @@ pub struct Song {
     pub stream_urls: Option<StreamUrls>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GaanaSongResponse {
     pub tracks: Option<Vec<GaanaTrack>>,
@@ -54,6 +57,7 @@ pub struct GaanaTrack {
     pub genre: Option<serde_json::Value>,
     #[serde(rename = "parental_warning")]
     pub explicit_content: Option<serde_json::Value>,
+    pub lyrics_url: Option<String>,
     pub language: Option<String>,
     #[serde(rename = "vendor_name")]
     pub label: Option<String>,
@@ -6,7 +6,7 @@ pub struct StreamUrls {
     pub very_high_quality: Option<String>, // 320kbps
     pub high_quality: Option<String>,      // 128kbps
     pub medium_quality: Option<String>,    // 64kbps
-    pub low_quality: Option<String>        // 16kbps
+    pub low_quality: Option<String>,       // 16kbps
 }
 
 impl StreamUrls {
@@ -20,7 +20,7 @@ impl StreamUrls {
             very_high_quality: vhq,
             high_quality: hq,
             medium_quality: mq,
-            low_quality: lq
+            low_quality: lq,
         }
     }
 }
@@ -0,0 +1,284 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use utoipa::IntoParams;
+
+use crate::models::error::ApiError;
+use crate::utils::validation::Validate;
+
+/// Response shaping parameters accepted by every endpoint
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct FieldsQuery {
+    /// Comma-separated fields to keep, e.g. `title,artists,images.small_artwork`
+    pub fields: Option<String>,
+    /// Comma-separated fields to drop, e.g. `stream_urls,images.large_artwork`
+    pub exclude: Option<String>,
+}
+
+/// Opt-in for the `{ "results": [...], "missing": [...] }` envelope on list endpoints
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct IncludeQuery {
+    /// Pass `missing` to get results wrapped with the items that could not be resolved,
+    /// instead of a bare array
+    pub include: Option<String>,
+}
+
+impl IncludeQuery {
+    pub fn wants_missing(&self) -> bool {
+        self.include
+            .as_deref()
+            .map(|include| include.split(',').any(|p| p.trim() == "missing"))
+            .unwrap_or(false)
+    }
+}
+
+impl Validate for IncludeQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        if let Some(unknown) = self
+            .include
+            .as_deref()
+            .into_iter()
+            .flat_map(|include| include.split(','))
+            .map(str::trim)
+            .find(|part| !part.is_empty() && *part != "missing")
+        {
+            return Err(ApiError::invalid_parameter(
+                "include",
+                &format!("Unknown value '{}'. Supported values: missing", unknown),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Nested field selection parsed from `a,b.c,b.d`; an empty node selects the whole value
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldTree(BTreeMap<String, FieldTree>);
+
+impl FieldTree {
+    pub fn parse(param: &str, spec: &str) -> Result<Self, ApiError> {
+        let mut tree = FieldTree::default();
+        for path in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let segments: Vec<&str> = path.split('.').map(str::trim).collect();
+            if segments.iter().any(|s| s.is_empty()) {
+                return Err(ApiError::invalid_parameter(
+                    param,
+                    &format!("Invalid field path '{}'", path),
+                ));
+            }
+            let mut node = &mut tree;
+            for segment in segments {
+                node = node.0.entry(segment.to_string()).or_default();
+            }
+        }
+        if tree.0.is_empty() {
+            return Err(ApiError::invalid_parameter(
+                param,
+                "Must list at least one field",
+            ));
+        }
+        Ok(tree)
+    }
+
+    fn keep(&self, object: Map<String, Value>) -> Map<String, Value> {
+        object
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let node = self.0.get(&key)?;
+                if node.0.is_empty() {
+                    Some((key, value))
+                } else {
+                    Some((key, node.apply(value, FieldTree::keep)))
+                }
+            })
+            .collect()
+    }
+
+    fn drop(&self, object: Map<String, Value>) -> Map<String, Value> {
+        object
+            .into_iter()
+            .filter_map(|(key, value)| match self.0.get(&key) {
+                Some(node) if node.0.is_empty() => None,
+                Some(node) => Some((key, node.apply(value, FieldTree::drop))),
+                None => Some((key, value)),
+            })
+            .collect()
+    }
+
+    /// Apply a selection to an object, or to each object of an array (e.g. album `tracks`)
+    fn apply(&self, value: Value, f: fn(&Self, Map<String, Value>) -> Map<String, Value>) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(f(self, object)),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.apply(v, f)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+/// Parsed `fields`/`exclude` pair for one request
+#[derive(Debug, Default)]
+pub struct Shape {
+    pub fields: Option<FieldTree>,
+    pub exclude: Option<FieldTree>,
+}
+
+impl Shape {
+    pub fn from_query(query: &FieldsQuery) -> Result<Self, ApiError> {
+        Ok(Self {
+            fields: query
+                .fields
+                .as_deref()
+                .map(|spec| FieldTree::parse("fields", spec))
+                .transpose()?,
+            exclude: query
+                .exclude
+                .as_deref()
+                .map(|spec| FieldTree::parse("exclude", spec))
+                .transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_none() && self.exclude.is_none()
+    }
+
+    /// Shape a response body: a bare entity, each item of a list, or the entities inside an
+    /// envelope (`results`/`entity` wrappers, or objects made only of lists like `/search`
+    /// and `/artists/graph`). `missing` and paging fields are left alone
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.apply_entity(v)).collect())
+            }
+            Value::Object(object) if is_envelope(&object) => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let shaped = match (key.as_str(), value) {
+                            ("missing", value) => value,
+                            ("results", Value::Object(entities)) => {
+                                // Batch results are keyed by the seokey or id they were asked for
+                                Value::Object(
+                                    entities
+                                        .into_iter()
+                                        .map(|(k, v)| (k, self.apply_entity(v)))
+                                        .collect(),
+                                )
+                            }
+                            ("entity", value) => self.apply_entity(value),
+                            (_, value @ (Value::Array(_) | Value::Object(_))) => self.apply(value),
+                            (_, value) => value,
+                        };
+                        (key, shaped)
+                    })
+                    .collect(),
+            ),
+            other => self.apply_entity(other),
+        }
+    }
+
+    fn apply_entity(&self, value: Value) -> Value {
+        let Value::Object(object) = value else {
+            return value;
+        };
+        let object = match &self.fields {
+            Some(tree) => tree.keep(object),
+            None => object,
+        };
+        let object = match &self.exclude {
+            Some(tree) => tree.drop(object),
+            None => object,
+        };
+        Value::Object(object)
+    }
+}
+
+/// Wrappers around entities rather than entities themselves
+fn is_envelope(object: &Map<String, Value>) -> bool {
+    object.contains_key("results")
+        || object.contains_key("entity")
+        || (!object.is_empty()
+            && object.iter().all(|(key, value)| {
+                key == "missing" || value.is_array() || value.as_object().is_some_and(is_envelope)
+            }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn shape(fields: Option<&str>, exclude: Option<&str>) -> Shape {
+        Shape::from_query(&FieldsQuery {
+            fields: fields.map(String::from),
+            exclude: exclude.map(String::from),
+        })
+        .unwrap()
+    }
+
+    fn song() -> Value {
+        json!({
+            "seokey": "tyler-herro",
+            "title": "Tyler Herro",
+            "artists": "Jack Harlow",
+            "duration": "02:36",
+            "images": { "large_artwork": "l", "medium_artwork": "m", "small_artwork": "s" }
+        })
+    }
+
+    #[test]
+    fn test_fields_keeps_nested_selection() {
+        let body = json!({ "results": [song()], "missing": [{ "seokey": "x", "reason": "gone" }] });
+        let shaped = shape(Some("title,images.small_artwork"), None).apply(body);
+        assert_eq!(
+            shaped,
+            json!({
+                "results": [{ "title": "Tyler Herro", "images": { "small_artwork": "s" } }],
+                "missing": [{ "seokey": "x", "reason": "gone" }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_exclude_drops_fields() {
+        let shaped = shape(None, Some("images.large_artwork,duration")).apply(song());
+        assert!(shaped.get("duration").is_none());
+        assert!(shaped["images"].get("large_artwork").is_none());
+        assert_eq!(shaped["images"]["small_artwork"], "s");
+    }
+
+    #[test]
+    fn test_shapes_entities_without_seokey() {
+        let genres = json!([{ "id": "romantic", "name": "Romantic", "languages": ["hindi"] }]);
+        assert_eq!(
+            shape(Some("id"), None).apply(genres),
+            json!([{ "id": "romantic" }])
+        );
+
+        let lyrics = json!({ "track_id": "1", "title": "Song", "plain": "la la" });
+        assert_eq!(
+            shape(None, Some("plain")).apply(lyrics),
+            json!({ "track_id": "1", "title": "Song" })
+        );
+
+        let graph = json!({
+            "nodes": [{ "seokey": "a", "name": "A", "image": "i" }],
+            "edges": [{ "from": "a", "to": "b", "weight": 1 }]
+        });
+        assert_eq!(
+            shape(None, Some("image,weight")).apply(graph),
+            json!({
+                "nodes": [{ "seokey": "a", "name": "A" }],
+                "edges": [{ "from": "a", "to": "b" }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_field_path() {
+        assert!(FieldTree::parse("fields", ",,").is_err());
+        assert!(FieldTree::parse("fields", "images..small").is_err());
+    }
+}
@@ -0,0 +1,366 @@
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::{
+    album::Album, error::ApiError, language::Languages, search::SearchDetail, song::Song,
+};
+use crate::utils::validation::{Validate, MAX_LIMIT};
+
+/// How many search hits to resolve per requested result when filters may drop some
+const FILTER_OVERFETCH: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    /// Keep Gaana's order
+    #[default]
+    Relevance,
+    Popularity,
+    ReleaseDate,
+    PlayCount,
+}
+
+/// Filters and ordering applied to resolved search results
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct SearchFilters {
+    /// Only keep results in these comma-separated languages, e.g. `Hindi,Punjabi`
+    /// (alias `language`)
+    #[serde(rename = "lang", alias = "language")]
+    lang: Option<String>,
+    /// `lang` parsed by `validate`, so a bad value is reported against `lang`
+    #[serde(skip)]
+    pub languages: Option<Languages>,
+    /// `false` drops explicit results, `true` keeps only explicit ones
+    pub explicit: Option<bool>,
+    /// Earliest release year, inclusive
+    pub year_from: Option<i32>,
+    /// Latest release year, inclusive
+    pub year_to: Option<i32>,
+    /// Shortest duration in seconds (albums: total of their tracks)
+    pub min_duration: Option<u32>,
+    /// Longest duration in seconds (albums: total of their tracks)
+    pub max_duration: Option<u32>,
+    /// Only keep results tagged with this genre, e.g. `Pop`
+    pub genre: Option<String>,
+    #[param(inline)]
+    pub sort: Option<SearchSort>,
+}
+
+impl Validate for SearchFilters {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.languages = self.lang.as_deref().map(Languages::parse).transpose()?;
+        if let (Some(from), Some(to)) = (self.year_from, self.year_to) {
+            if from > to {
+                return Err(ApiError::invalid_parameter(
+                    "year_from",
+                    "Must not be after year_to",
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_duration, self.max_duration) {
+            if min > max {
+                return Err(ApiError::invalid_parameter(
+                    "min_duration",
+                    "Must not be greater than max_duration",
+                ));
+            }
+        }
+        if let Some(genre) = &self.genre {
+            let genre = genre.trim();
+            if genre.is_empty() {
+                return Err(ApiError::invalid_parameter("genre", "Must not be empty"));
+            }
+            self.genre = Some(genre.to_string());
+        }
+        Ok(())
+    }
+}
+
+impl SearchFilters {
+    /// Whether any filter (not just sorting) was requested
+    pub fn filters(&self) -> bool {
+        self.languages.is_some()
+            || self.explicit.is_some()
+            || self.year_from.is_some()
+            || self.year_to.is_some()
+            || self.min_duration.is_some()
+            || self.max_duration.is_some()
+            || self.genre.is_some()
+    }
+
+    /// Whether albums must be resolved with their tracks to evaluate the filters
+    pub fn needs_tracks(&self) -> bool {
+        self.explicit.is_some()
+            || self.min_duration.is_some()
+            || self.max_duration.is_some()
+            || self.genre.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.filters() && self.sort.unwrap_or_default() == SearchSort::Relevance
+    }
+
+    /// Number of search hits to resolve so that `limit` results can survive filtering
+    pub fn candidates(&self, limit: usize) -> usize {
+        if self.filters() {
+            overfetch(limit)
+        } else {
+            limit
+        }
+    }
+
+    /// Filters and sorting only work on full entities, not on `detail=none|basic` hits
+    pub fn check_detail(&self, detail: SearchDetail) -> Result<(), ApiError> {
+        if detail != SearchDetail::Full && !self.is_empty() {
+            return Err(ApiError::invalid_parameter(
+                "detail",
+                "Filters and sorting require detail=full",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Filter, sort and truncate resolved results in place
+    pub fn apply<T: Filterable>(&self, items: &mut Vec<T>, limit: usize) {
+        items.retain(|item| self.matches(item));
+        match self.sort.unwrap_or_default() {
+            SearchSort::Relevance => {}
+            SearchSort::Popularity => items.sort_by_key(|i| std::cmp::Reverse(i.popularity())),
+            SearchSort::PlayCount => items.sort_by_key(|i| std::cmp::Reverse(i.play_count())),
+            SearchSort::ReleaseDate => {
+                items.sort_by(|a, b| b.release_date().cmp(&a.release_date()))
+            }
+        }
+        items.truncate(limit);
+    }
+
+    fn matches<T: Filterable>(&self, item: &T) -> bool {
+        if let Some(languages) = &self.languages {
+            if !item
+                .language()
+                .map(|l| languages.matches(l))
+                .unwrap_or(false)
+            {
+                return false;
+            }
+        }
+        if let Some(explicit) = self.explicit {
+            if item.is_explicit() != explicit {
+                return false;
+            }
+        }
+        if self.year_from.is_some() || self.year_to.is_some() {
+            match item.release_year() {
+                Some(year) => {
+                    if self.year_from.map(|from| year < from).unwrap_or(false)
+                        || self.year_to.map(|to| year > to).unwrap_or(false)
+                    {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+        if self.min_duration.is_some() || self.max_duration.is_some() {
+            match item.duration_secs() {
+                Some(secs) => {
+                    if self.min_duration.map(|min| secs < min).unwrap_or(false)
+                        || self.max_duration.map(|max| secs > max).unwrap_or(false)
+                    {
+                        return false;
+                    }
+                }
+                None => return false,
+            }
+        }
+        if let Some(genre) = &self.genre {
+            if !item.genres().iter().any(|g| g.eq_ignore_ascii_case(genre)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Normalized models that search filters and sorting can inspect
+pub trait Filterable {
+    fn language(&self) -> Option<&str>;
+    fn is_explicit(&self) -> bool;
+    fn release_date(&self) -> Option<&str>;
+    fn duration_secs(&self) -> Option<u32>;
+    fn genres(&self) -> Vec<String>;
+    fn popularity(&self) -> i64;
+    fn play_count(&self) -> u64;
+
+    fn release_year(&self) -> Option<i32> {
+        self.release_date()?.get(..4)?.parse().ok()
+    }
+}
+
+impl Filterable for Song {
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    fn is_explicit(&self) -> bool {
+        self.is_explicit.unwrap_or(0) != 0
+    }
+
+    fn release_date(&self) -> Option<&str> {
+        self.release_date.as_deref()
+    }
+
+    fn duration_secs(&self) -> Option<u32> {
+        self.duration.as_deref().and_then(parse_duration)
+    }
+
+    fn genres(&self) -> Vec<String> {
+        self.genres
+            .as_deref()
+            .map(|g| g.split(',').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Gaana's `popularity` looks like `1234~5`; fall back to favourites
+    fn popularity(&self) -> i64 {
+        self.popularity
+            .as_deref()
+            .and_then(|p| p.split('~').next())
+            .and_then(|p| p.trim().parse().ok())
+            .unwrap_or_else(|| self.favorite_count.unwrap_or(0) as i64)
+    }
+
+    fn play_count(&self) -> u64 {
+        self.play_count
+            .as_deref()
+            .and_then(parse_count)
+            .unwrap_or(0)
+    }
+}
+
+/// Albums answer explicit, duration and genre questions from their tracks
+impl Filterable for Album {
+    fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    fn is_explicit(&self) -> bool {
+        self.tracks
+            .iter()
+            .flatten()
+            .any(|track| track.is_explicit())
+    }
+
+    fn release_date(&self) -> Option<&str> {
+        self.release_date.as_deref()
+    }
+
+    fn duration_secs(&self) -> Option<u32> {
+        let tracks = self.tracks.as_ref().filter(|t| !t.is_empty())?;
+        tracks.iter().try_fold(0u32, |total, track| {
+            total.checked_add(track.duration_secs()?)
+        })
+    }
+
+    fn genres(&self) -> Vec<String> {
+        let mut genres: Vec<String> = self
+            .tracks
+            .iter()
+            .flatten()
+            .flat_map(|track| track.genres())
+            .collect();
+        genres.sort();
+        genres.dedup();
+        genres
+    }
+
+    fn popularity(&self) -> i64 {
+        self.favorite_count.unwrap_or(0) as i64
+    }
+
+    fn play_count(&self) -> u64 {
+        self.play_count
+            .as_deref()
+            .and_then(parse_count)
+            .unwrap_or(0)
+    }
+}
+
+/// Number of search hits to resolve when post-filtering may drop some of them
+pub fn overfetch(limit: usize) -> usize {
+    (limit * FILTER_OVERFETCH).min(MAX_LIMIT)
+}
+
+/// Parse a duration given in seconds (`156`) or as `mm:ss` / `hh:mm:ss`;
+/// values too large for `u32` are treated as unparseable
+pub fn parse_duration(value: &str) -> Option<u32> {
+    value.trim().split(':').try_fold(0u32, |total, part| {
+        total
+            .checked_mul(60)?
+            .checked_add(part.trim().parse::<u32>().ok()?)
+    })
+}
+
+/// Parse counts such as `1234`, `12K+` or `1.5M`
+pub fn parse_count(value: &str) -> Option<u64> {
+    let value = value.trim().trim_end_matches('+').replace(',', "");
+    let (number, multiplier) = match value.chars().last()?.to_ascii_uppercase() {
+        'K' => (&value[..value.len() - 1], 1_000.0),
+        'M' => (&value[..value.len() - 1], 1_000_000.0),
+        'B' => (&value[..value.len() - 1], 1_000_000_000.0),
+        _ => (value.as_str(), 1.0),
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|n| (n * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_formats() {
+        assert_eq!(parse_duration("156"), Some(156));
+        assert_eq!(parse_duration("02:36"), Some(156));
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("99999999:99999999:99"), None);
+    }
+
+    #[test]
+    fn test_parse_count_suffixes() {
+        assert_eq!(parse_count("1234"), Some(1234));
+        assert_eq!(parse_count("12K+"), Some(12_000));
+        assert_eq!(parse_count("1.5M"), Some(1_500_000));
+        assert_eq!(parse_count(""), None);
+    }
+
+    #[test]
+    fn test_lang_is_parsed_by_validate() {
+        let mut filters = SearchFilters {
+            lang: Some("hi, Punjabi".to_string()),
+            ..Default::default()
+        };
+        filters.validate().unwrap();
+        assert_eq!(filters.languages.unwrap().as_slice().len(), 2);
+
+        let mut filters = SearchFilters {
+            lang: Some("Klingon".to_string()),
+            ..Default::default()
+        };
+        assert!(filters.validate().unwrap_err().error.contains("lang"));
+    }
+
+    #[test]
+    fn test_year_range_is_validated() {
+        let mut filters = SearchFilters {
+            year_from: Some(2022),
+            year_to: Some(2020),
+            ..Default::default()
+        };
+        assert!(filters.validate().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::search::EntityType;
 use serde_json::Value;
 
 /// Extract ID from JSON value (could be string or number)
@@ -18,6 +19,72 @@ pub fn extract_int(value: &Option<Value>) -> Option<i32> {
     }
 }
 
+/// Collect the `seokey` of every entity in the array stored under `key`
+pub fn extract_seokeys(response: &Value, key: &str) -> Vec<String> {
+    response
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|entities| {
+            entities
+                .iter()
+                .filter_map(|entity| entity.get("seokey").and_then(|s| s.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Entity type of a search group (`gr` item), from its `ty` field
+pub fn search_group_type(group: &Value) -> Option<EntityType> {
+    group
+        .get("ty")
+        .and_then(|t| t.as_str())
+        .and_then(EntityType::from_group_type)
+}
+
+/// Search group (`gr` item) holding `entity_type`
+pub fn find_search_group(response: &Value, entity_type: EntityType) -> Option<&Value> {
+    response
+        .get("gr")
+        .and_then(|gr| gr.as_array())
+        .and_then(|groups| {
+            groups
+                .iter()
+                .find(|group| search_group_type(group) == Some(entity_type))
+        })
+}
+
+/// First search group, which holds every hit when the search was narrowed with `secType`
+pub fn first_search_group(response: &Value) -> Option<&Value> {
+    response
+        .get("gr")
+        .and_then(|gr| gr.as_array())
+        .and_then(|groups| groups.first())
+}
+
+/// Up to `limit` entries (`gd`) of a search group
+pub fn search_group_items(group: Option<&Value>, limit: usize) -> Vec<&Value> {
+    group
+        .and_then(|group| group.get("gd"))
+        .and_then(|gd| gd.as_array())
+        .map(|items| items.iter().take(limit).collect())
+        .unwrap_or_default()
+}
+
+/// Collect up to `limit` seokeys from the search group holding `entity_type`
+pub fn extract_search_group_seokeys(
+    response: &Value,
+    entity_type: EntityType,
+    limit: usize,
+) -> Vec<String> {
+    search_group_items(find_search_group(response, entity_type), usize::MAX)
+        .into_iter()
+        .filter_map(|item| item.get("seo").and_then(|s| s.as_str()))
+        .take(limit)
+        .map(|s| s.to_string())
+        .collect()
+}
+
 /// Process artist information from Gaana API response
 pub fn process_artists(artist_value: &Option<Value>) -> (String, String, String, Option<String>) {
     match artist_value {
@@ -110,37 +177,116 @@ pub fn process_genres(genre_value: &Option<Value>) -> Option<String> {
     }
 }
 
-/// Validate and normalize language parameter
-pub fn validate_language(lang: &str) -> String {
-    let valid_languages = [
-        "English",
-        "Hindi",
-        "Punjabi",
-        "Telugu",
-        "Tamil",
-        "Bengali",
-        "Gujarati",
-        "Kannada",
-        "Malayalam",
-        "Marathi",
-        "Odia",
-        "Assamese",
-    ];
-
-    if valid_languages.contains(&lang) {
-        lang.to_string()
-    } else {
-        "Telugu".to_string() // Default fallback
+/// Merge lists by taking one item from each in turn, e.g. results fetched per language
+pub fn interleave<T>(lists: Vec<Vec<T>>) -> Vec<T> {
+    let mut iters: Vec<_> = lists.into_iter().map(|list| list.into_iter()).collect();
+    let mut merged = Vec::new();
+    loop {
+        let before = merged.len();
+        merged.extend(iters.iter_mut().filter_map(|iter| iter.next()));
+        if merged.len() == before {
+            return merged;
+        }
     }
 }
 
-/// Extract SEO key from Gaana URL
-pub fn extract_seokey_from_url(url: &str) -> Option<String> {
-    url.split('/').last().map(|s| s.to_string())
+/// Hosts whose links are Gaana entity pages or redirect to one
+const GAANA_HOSTS: &[&str] = &["gaana.com", "gaana.page.link", "gaana.app.link"];
+
+/// Parse a pasted link, tolerating a missing scheme, and keep it only if it points at Gaana
+pub fn parse_gaana_url(raw: &str) -> Option<url::Url> {
+    let raw = raw.trim();
+    let parsed = url::Url::parse(raw)
+        .or_else(|_| url::Url::parse(&format!("https://{}", raw)))
+        .ok()?;
+    is_gaana_url(&parsed).then_some(parsed)
+}
+
+/// Whether a URL is served over http(s) by Gaana or one of its share-link domains
+pub fn is_gaana_url(url: &url::Url) -> bool {
+    let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+        return false;
+    };
+    let known = GAANA_HOSTS
+        .iter()
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)));
+    known && matches!(url.scheme(), "http" | "https")
+}
+
+/// Entity type and seokey of a Gaana page URL such as `https://gaana.com/song/tum-hi-ho/?utm=x`;
+/// query strings, fragments, trailing slashes and leading locale segments are ignored
+fn entity_from_url(url: &url::Url) -> Option<(EntityType, String)> {
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    segments.windows(2).find_map(|pair| {
+        let entity_type = match pair[0].to_ascii_lowercase().as_str() {
+            "song" | "lyrics" => EntityType::Song,
+            "album" => EntityType::Album,
+            "artist" => EntityType::Artist,
+            "playlist" => EntityType::Playlist,
+            _ => return None,
+        };
+        Some((entity_type, pair[1].to_string()))
+    })
+}
+
+/// Extract entity type and SEO key from Gaana URL
+pub fn extract_seokey_from_url(url: &str) -> Option<(EntityType, String)> {
+    entity_from_url(&parse_gaana_url(url)?)
 }
 
 /// Limit results to specified count
+#[allow(dead_code)]
 pub fn limit_results<T>(results: Vec<T>, limit: Option<usize>) -> Vec<T> {
     let limit = limit.unwrap_or(10);
     results.into_iter().take(limit).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_from_url_variants() {
+        assert_eq!(
+            extract_seokey_from_url("https://gaana.com/song/tum-hi-ho"),
+            Some((EntityType::Song, "tum-hi-ho".to_string()))
+        );
+        assert_eq!(
+            extract_seokey_from_url("gaana.com/album/aashiqui-2/?utm_source=share#top"),
+            Some((EntityType::Album, "aashiqui-2".to_string()))
+        );
+        assert_eq!(
+            extract_seokey_from_url("https://www.gaana.com/hi/artist/arijit-singh"),
+            Some((EntityType::Artist, "arijit-singh".to_string()))
+        );
+        assert_eq!(
+            extract_seokey_from_url("https://m.gaana.com/playlist/gaana-dj-top-50//"),
+            Some((EntityType::Playlist, "gaana-dj-top-50".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_interleave_takes_turns() {
+        assert_eq!(
+            interleave(vec![vec![1, 2, 3], vec![], vec![10, 20]]),
+            vec![1, 10, 2, 20, 3]
+        );
+    }
+
+    #[test]
+    fn test_rejects_foreign_or_unknown_urls() {
+        assert!(parse_gaana_url("https://example.com/song/x").is_none());
+        assert!(parse_gaana_url("https://notgaana.com/song/x").is_none());
+        assert!(extract_seokey_from_url("https://gaana.com/podcast/x").is_none());
+        assert!(extract_seokey_from_url("https://gaana.com/").is_none());
+    }
+
+    #[test]
+    fn test_share_link_redirect_hosts() {
+        let url = |raw: &str| url::Url::parse(raw).unwrap();
+        assert!(is_gaana_url(&url("https://gaana.page.link/abc")));
+        assert!(is_gaana_url(&url("https://www.gaana.com/song/x")));
+        assert!(!is_gaana_url(&url("http://169.254.169.254/latest")));
+        assert!(!is_gaana_url(&url("ftp://gaana.com/song/x")));
+    }
+}
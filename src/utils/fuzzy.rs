@@ -0,0 +1,259 @@
+use crate::models::song::Song;
+
+/// Most query variants sent to Gaana for one fuzzy search, the original included
+pub const MAX_VARIANTS: usize = 4;
+
+/// Spelling swaps common in romanized Indian titles, tried one at a time for variants
+const SPELLING_SWAPS: &[(&str, &str)] = &[
+    ("iya", "ia"),
+    ("ia", "iya"),
+    ("aa", "a"),
+    ("ee", "i"),
+    ("oo", "u"),
+    ("w", "v"),
+    ("v", "w"),
+    ("ph", "f"),
+    ("z", "j"),
+    ("sh", "s"),
+];
+
+/// One-directional form of the swaps, used to compare spellings
+const SKELETON_RULES: &[(&str, &str)] = &[
+    ("iya", "ia"),
+    ("aa", "a"),
+    ("ee", "i"),
+    ("oo", "u"),
+    ("w", "v"),
+    ("ph", "f"),
+    ("z", "j"),
+    ("sh", "s"),
+];
+
+/// Query variants to search for: the query itself, its romanization when written in
+/// Devanagari, and single spelling swaps of the romanized form
+pub fn variants(query: &str) -> Vec<String> {
+    let original = query.trim().to_lowercase();
+    let romanized = transliterate(&original);
+    let mut variants = vec![original.clone()];
+    if romanized != original {
+        variants.push(romanized.clone());
+    }
+    for (from, to) in SPELLING_SWAPS {
+        if romanized.contains(from) {
+            variants.push(romanized.replace(from, to));
+        }
+    }
+    let mut seen = Vec::new();
+    variants.retain(|v| {
+        let fresh = !v.is_empty() && !seen.contains(v);
+        seen.push(v.clone());
+        fresh
+    });
+    variants.truncate(MAX_VARIANTS);
+    variants
+}
+
+/// Keep the first song per `track_id` and order by similarity to the query, best first
+pub fn dedupe_and_rerank(query: &str, songs: &mut Vec<Song>) {
+    let mut seen = Vec::new();
+    songs.retain(|song| {
+        let fresh = !seen.contains(&song.track_id);
+        seen.push(song.track_id.clone());
+        fresh
+    });
+    let mut scored: Vec<(f64, Song)> = songs
+        .drain(..)
+        .map(|song| (similarity(query, &song.title), song))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    songs.extend(scored.into_iter().map(|(_, song)| song));
+}
+
+/// Similarity in `0.0..=1.0`, comparing both the raw text and its spelling skeleton
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let plain = ratio(&a.trim().to_lowercase(), &b.trim().to_lowercase());
+    let skeletal = ratio(&skeleton(a), &skeleton(b));
+    plain.max(skeletal)
+}
+
+/// Romanized, lowercase form with the skeleton rules applied and doubled letters collapsed, so `Kesariya` and `kesaria` compare equal
+fn skeleton(text: &str) -> String {
+    let mut text: String = transliterate(&text.to_lowercase())
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    for (from, to) in SKELETON_RULES {
+        text = text.replace(from, to);
+    }
+    let mut collapsed = String::with_capacity(text.len());
+    for c in text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+    {
+        if !collapsed.ends_with(c) {
+            collapsed.push(c);
+        }
+    }
+    collapsed
+}
+
+fn ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Romanize Devanagari text the way titles are usually spelled on Gaana; other text is kept
+pub fn transliterate(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if let Some(consonant) = devanagari_consonant(c) {
+            out.push_str(consonant);
+            let next = chars.get(i + 1).copied();
+            let ends_word = next.map(|n| !is_devanagari(n)).unwrap_or(true);
+            let takes_vowel = next
+                .map(|n| devanagari_sign(n).is_some() || n == '\u{094D}')
+                .unwrap_or(false);
+            // Inherent vowel, dropped before a sign and at the end of a word
+            if !takes_vowel && !ends_word {
+                out.push('a');
+            }
+        } else if let Some(vowel) = devanagari_vowel(c).or_else(|| devanagari_sign(c)) {
+            out.push_str(vowel);
+        } else if c == '\u{094D}' || c == '\u{093C}' {
+            // Virama and nukta add no letters of their own
+        } else if c == '\u{0902}' || c == '\u{0901}' {
+            out.push('n');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn is_devanagari(c: char) -> bool {
+    ('\u{0900}'..='\u{097F}').contains(&c)
+}
+
+fn devanagari_vowel(c: char) -> Option<&'static str> {
+    Some(match c {
+        'अ' => "a",
+        'आ' => "aa",
+        'इ' => "i",
+        'ई' => "ee",
+        'उ' => "u",
+        'ऊ' => "oo",
+        'ए' => "e",
+        'ऐ' => "ai",
+        'ओ' => "o",
+        'औ' => "au",
+        'ऋ' => "ri",
+        _ => return None,
+    })
+}
+
+/// Dependent vowel signs (matras)
+fn devanagari_sign(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ा' => "a",
+        'ि' => "i",
+        'ी' => "i",
+        'ु' => "u",
+        'ू' => "u",
+        'े' => "e",
+        'ै' => "ai",
+        'ो' => "o",
+        'ौ' => "au",
+        'ृ' => "ri",
+        _ => return None,
+    })
+}
+
+fn devanagari_consonant(c: char) -> Option<&'static str> {
+    Some(match c {
+        'क' => "k",
+        'ख' => "kh",
+        'ग' => "g",
+        'घ' => "gh",
+        'ङ' => "n",
+        'च' => "ch",
+        'छ' => "chh",
+        'ज' => "j",
+        'झ' => "jh",
+        'ञ' => "n",
+        'ट' => "t",
+        'ठ' => "th",
+        'ड' => "d",
+        'ढ' => "dh",
+        'ण' => "n",
+        'त' => "t",
+        'थ' => "th",
+        'द' => "d",
+        'ध' => "dh",
+        'न' => "n",
+        'प' => "p",
+        'फ' => "ph",
+        'ब' => "b",
+        'भ' => "bh",
+        'म' => "m",
+        'य' => "y",
+        'र' => "r",
+        'ल' => "l",
+        'व' => "v",
+        'श' => "sh",
+        'ष' => "sh",
+        'स' => "s",
+        'ह' => "h",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_include_common_spellings() {
+        let variants = variants("Kesariya");
+        assert_eq!(variants[0], "kesariya");
+        assert!(variants.contains(&"kesaria".to_string()));
+        assert!(variants.len() <= MAX_VARIANTS);
+    }
+
+    #[test]
+    fn test_transliterates_devanagari() {
+        assert_eq!(transliterate("केसरिया"), "kesariya");
+        assert_eq!(transliterate("tum hi ho"), "tum hi ho");
+    }
+
+    #[test]
+    fn test_similarity_ignores_spelling_variants() {
+        assert_eq!(similarity("kesaria", "Kesariya"), 1.0);
+        assert_eq!(similarity("dil diyan gallan", "Dil Diyaan Gallaan"), 1.0);
+        assert!(similarity("kesariya", "Kesariya") > similarity("kesariya", "Tum Hi Ho"));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::models::genre::{Genre, GenreKind};
+use crate::models::language::{Language, Languages};
+
+/// A browsable genre or mood: id, display name, kind and the languages it belongs to
+/// (empty for all languages)
+pub struct GenreEntry {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub kind: GenreKind,
+    pub languages: &'static [Language],
+}
+
+const fn genre(id: &'static str, name: &'static str, languages: &'static [Language]) -> GenreEntry {
+    GenreEntry {
+        id,
+        name,
+        kind: GenreKind::Genre,
+        languages,
+    }
+}
+
+const fn mood(id: &'static str, name: &'static str) -> GenreEntry {
+    GenreEntry {
+        id,
+        name,
+        kind: GenreKind::Mood,
+        languages: &[],
+    }
+}
+
+/// Genres and moods Gaana curates content for
+pub const GENRES: &[GenreEntry] = &[
+    genre("bollywood", "Bollywood", &[Language::Hindi]),
+    genre("pop", "Pop", &[]),
+    genre("rock", "Rock", &[]),
+    genre("hip-hop", "Hip Hop", &[]),
+    genre("edm", "EDM", &[Language::English]),
+    genre("indie", "Indie", &[]),
+    genre("classical", "Classical", &[]),
+    genre("devotional", "Devotional", &[]),
+    genre("folk", "Folk", &[]),
+    genre("ghazal", "Ghazal", &[Language::Hindi]),
+    genre("sufi", "Sufi", &[Language::Hindi, Language::Punjabi]),
+    genre("bhangra", "Bhangra", &[Language::Punjabi]),
+    genre(
+        "carnatic",
+        "Carnatic",
+        &[
+            Language::Tamil,
+            Language::Telugu,
+            Language::Kannada,
+            Language::Malayalam,
+        ],
+    ),
+    genre("rabindra-sangeet", "Rabindra Sangeet", &[Language::Bengali]),
+    genre("lavani", "Lavani", &[Language::Marathi]),
+    genre("garba", "Garba", &[Language::Gujarati]),
+    genre("sambalpuri", "Sambalpuri", &[Language::Odia]),
+    genre("bihu", "Bihu", &[Language::Assamese]),
+    mood("romantic", "Romantic"),
+    mood("party", "Party"),
+    mood("sad", "Sad"),
+    mood("workout", "Workout"),
+    mood("chill", "Chill"),
+    mood("retro", "Retro"),
+];
+
+/// Catalog entry for a genre id, case-insensitively
+pub fn find(id: &str) -> Option<&'static GenreEntry> {
+    GENRES.iter().find(|g| g.id.eq_ignore_ascii_case(id.trim()))
+}
+
+/// Genres available in any of `languages`, or all of them
+pub fn available(languages: Option<&Languages>) -> Vec<Genre> {
+    GENRES
+        .iter()
+        .filter(|g| match languages {
+            Some(languages) => {
+                g.languages.is_empty()
+                    || g.languages.iter().any(|l| languages.as_slice().contains(l))
+            }
+            None => true,
+        })
+        .map(GenreEntry::to_genre)
+        .collect()
+}
+
+impl GenreEntry {
+    /// Keyword searched on Gaana for this genre, narrowed to a language when given
+    pub fn keyword(&self, language: Option<Language>) -> String {
+        match language {
+            Some(language) => format!("{} {}", language, self.name),
+            None => self.name.to_string(),
+        }
+    }
+
+    pub fn to_genre(&self) -> Genre {
+        Genre {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            kind: self.kind,
+            languages: self.languages.to_vec(),
+            songs_url: format!("/genres/{}/songs", self.id),
+            playlists_url: format!("/genres/{}/playlists", self.id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_is_case_insensitive() {
+        assert_eq!(find("Hip-Hop").map(|g| g.name), Some("Hip Hop"));
+        assert!(find("polka").is_none());
+    }
+
+    #[test]
+    fn test_available_by_language() {
+        let punjabi: Vec<String> = available(Some(&Language::Punjabi.into()))
+            .into_iter()
+            .map(|g| g.id)
+            .collect();
+        assert!(punjabi.contains(&"bhangra".to_string()));
+        assert!(punjabi.contains(&"romantic".to_string()));
+        assert!(!punjabi.contains(&"garba".to_string()));
+        assert_eq!(available(None).len(), GENRES.len());
+    }
+}
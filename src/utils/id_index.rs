@@ -0,0 +1,29 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::models::search::EntityType;
+use crate::utils::cache::TtlCache;
+
+/// Ids and seokeys rarely change, so mappings are kept for a day
+const ID_INDEX_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const ID_INDEX_CAPACITY: usize = 50_000;
+
+/// Every entity normalized by this server, by numeric id, so `id=` lookups can skip Gaana
+static ID_INDEX: LazyLock<TtlCache<String>> =
+    LazyLock::new(|| TtlCache::new(ID_INDEX_TTL, ID_INDEX_CAPACITY));
+
+fn key(entity_type: EntityType, id: &str) -> String {
+    format!("{:?}:{}", entity_type, id)
+}
+
+pub fn remember(entity_type: EntityType, id: &str, seokey: &str) {
+    if id.is_empty() || id == "0" || seokey.is_empty() {
+        return;
+    }
+    ID_INDEX.insert(key(entity_type, id), seokey.to_string());
+}
+
+pub fn lookup(entity_type: EntityType, id: &str) -> Option<String> {
+    ID_INDEX.get(&key(entity_type, id))
+}
@@ -0,0 +1,229 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderName},
+};
+use serde::Deserialize;
+use std::env;
+use std::sync::LazyLock;
+use utoipa::IntoParams;
+
+use crate::models::error::ApiError;
+use crate::models::language::{Language, Languages};
+use crate::utils::validation::{Validate, ValidatedQuery};
+
+/// Response header naming where the chosen language came from
+pub const LANGUAGE_SOURCE_HEADER: &str = "x-language-source";
+
+/// `DEFAULT_LANGUAGE` from the environment; overrides each endpoint's own default
+static DEFAULT_LANGUAGE: LazyLock<Option<Language>> = LazyLock::new(|| {
+    let value = env::var("DEFAULT_LANGUAGE").ok()?;
+    match Language::parse(&value) {
+        Ok(language) => Some(language),
+        Err(e) => {
+            eprintln!("Ignoring DEFAULT_LANGUAGE: {}", e.message);
+            None
+        }
+    }
+});
+
+impl Language {
+    /// Parse a Gaana name or ISO code, case-insensitively
+    pub fn parse(value: &str) -> Result<Self, ApiError> {
+        let trimmed = value.trim();
+        Language::ALL
+            .into_iter()
+            .find(|l| {
+                l.name().eq_ignore_ascii_case(trimmed) || l.code().eq_ignore_ascii_case(trimmed)
+            })
+            .ok_or_else(|| {
+                ApiError::invalid_parameter(
+                    "lang",
+                    &format!(
+                        "Unsupported language '{}'. Supported languages: {}",
+                        trimmed,
+                        Language::ALL.map(Language::name).join(", ")
+                    ),
+                )
+            })
+    }
+}
+
+impl Languages {
+    /// Parse a comma-separated list of names or ISO codes, dropping repeats
+    pub fn parse(value: &str) -> Result<Self, ApiError> {
+        let languages: Languages = value
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(Language::parse)
+            .collect::<Result<_, _>>()?;
+        if languages.as_slice().is_empty() {
+            return Err(ApiError::invalid_parameter("lang", "Must not be empty"));
+        }
+        Ok(languages)
+    }
+}
+
+/// Language selection shared by every endpoint that narrows content by language
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct LanguageQuery {
+    /// One or more comma-separated languages, by name or ISO code, e.g. `Hindi,Punjabi` or `hi,pa`
+    /// (alias `language`)
+    #[serde(rename = "lang", alias = "language")]
+    lang: Option<String>,
+    /// `lang` parsed by `validate`, so a bad value is reported against `lang`
+    #[serde(skip)]
+    pub languages: Option<Languages>,
+}
+
+impl Validate for LanguageQuery {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        self.languages = self.lang.as_deref().map(Languages::parse).transpose()?;
+        Ok(())
+    }
+}
+
+/// Where the languages of a request came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageSource {
+    Query,
+    AcceptLanguage,
+    Default,
+}
+
+impl LanguageSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LanguageSource::Query => "query",
+            LanguageSource::AcceptLanguage => "accept-language",
+            LanguageSource::Default => "default",
+        }
+    }
+}
+
+/// Language selection for endpoints that always need a language: `lang` when given, otherwise
+/// the best supported language in `Accept-Language`, otherwise the configured default
+pub struct PreferredLanguages {
+    pub lang: Option<Languages>,
+    pub accept_language: Option<Language>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PreferredLanguages
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ValidatedQuery(query) =
+            ValidatedQuery::<LanguageQuery>::from_request_parts(parts, state).await?;
+        let accept_language = parts
+            .headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_accept_language);
+        Ok(Self {
+            lang: query.languages,
+            accept_language,
+        })
+    }
+}
+
+impl PreferredLanguages {
+    /// Languages to use, falling back to `DEFAULT_LANGUAGE` or else the endpoint's `fallback`
+    pub fn resolve(self, fallback: Language) -> ChosenLanguages {
+        let (languages, source) = match (self.lang, self.accept_language) {
+            (Some(languages), _) => (languages, LanguageSource::Query),
+            (None, Some(language)) => (language.into(), LanguageSource::AcceptLanguage),
+            (None, None) => (
+                DEFAULT_LANGUAGE.unwrap_or(fallback).into(),
+                LanguageSource::Default,
+            ),
+        };
+        ChosenLanguages { languages, source }
+    }
+}
+
+/// Languages a response was built for, echoed back in its headers
+pub struct ChosenLanguages {
+    pub languages: Languages,
+    pub source: LanguageSource,
+}
+
+impl ChosenLanguages {
+    /// `Content-Language` with the ISO codes, where they came from, and `Vary` for caches
+    pub fn headers(&self) -> [(HeaderName, String); 3] {
+        let codes: Vec<&str> = self.languages.as_slice().iter().map(|l| l.code()).collect();
+        [
+            (header::CONTENT_LANGUAGE, codes.join(", ")),
+            (
+                HeaderName::from_static(LANGUAGE_SOURCE_HEADER),
+                self.source.as_str().to_string(),
+            ),
+            (header::VARY, header::ACCEPT_LANGUAGE.to_string()),
+        ]
+    }
+}
+
+/// Best supported language in an `Accept-Language` header such as `hi-IN,hi;q=0.9,en;q=0.8`,
+/// matched on the primary subtag; `*` and `q=0` entries are ignored
+pub fn parse_accept_language(value: &str) -> Option<Language> {
+    let mut ranges: Vec<(f32, Language)> = value
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            let primary = tag.split(['-', '_']).next()?;
+            let language = Language::ALL
+                .into_iter()
+                .find(|l| l.code().eq_ignore_ascii_case(primary))?;
+            (quality > 0.0).then_some((quality, language))
+        })
+        .collect();
+    // Stable, so equally weighted languages keep the header's order
+    ranges.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    ranges.first().map(|(_, language)| *language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names_and_codes() {
+        assert_eq!(Language::parse(" hindi ").unwrap(), Language::Hindi);
+        assert_eq!(Language::parse("PA").unwrap(), Language::Punjabi);
+        assert!(Language::parse("Klingon").is_err());
+    }
+
+    #[test]
+    fn test_parse_language_lists() {
+        let languages = Languages::parse("Hindi, punjabi,hi,").unwrap();
+        assert_eq!(languages.as_slice(), &[Language::Hindi, Language::Punjabi]);
+        assert_eq!(languages.join(), "Hindi,Punjabi");
+        assert_eq!(languages.single(), None);
+        assert!(languages.matches("PUNJABI"));
+        assert!(Languages::parse(" , ").is_err());
+        assert!(Languages::parse("Hindi,Klingon").is_err());
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("hi-IN,hi;q=0.9,en-US;q=0.8"),
+            Some(Language::Hindi)
+        );
+        assert_eq!(
+            parse_accept_language("fr-FR, ta;q=0.5, te;q=0.7"),
+            Some(Language::Telugu)
+        );
+        assert_eq!(parse_accept_language("pa;q=0, *"), None);
+        assert_eq!(parse_accept_language("de-DE,fr"), None);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::models::lyrics::LyricLine;
+use serde_json::Value;
+
+/// Fields of Gaana's lyrics response that may hold the lyrics text, best first
+const LYRICS_FIELDS: &[&str] = &["lrc", "synced_lyrics", "lyrics", "lyric", "lyrics_text"];
+
+/// Lyrics text from a Gaana lyrics response, either as a string field or nested under `text`
+pub fn extract_lyrics_text(response: &Value) -> Option<String> {
+    LYRICS_FIELDS.iter().find_map(|field| {
+        let value = response.get(field)?;
+        value
+            .as_str()
+            .or_else(|| value.get("text").and_then(|t| t.as_str()))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    })
+}
+
+/// Timed lines of LRC text such as `[00:12.34]Tum hi ho`, sorted by time. Lines with
+/// several timestamps are repeated; metadata tags (`[ar:...]`) and untimed lines are dropped.
+pub fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for line in normalize_breaks(text).lines() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            match parse_timestamp(tag.0) {
+                Some(time_ms) => times.push(time_ms),
+                None => break,
+            }
+            rest = tag.1;
+        }
+        let text = rest.trim();
+        lines.extend(times.into_iter().map(|time_ms| LyricLine {
+            time_ms,
+            text: text.to_string(),
+        }));
+    }
+    lines.sort_by_key(|line| line.time_ms);
+    lines
+}
+
+/// Plain lyrics: the text of synced lines, or the raw text without tags and HTML breaks
+pub fn to_plain(text: &str, synced: &[LyricLine]) -> String {
+    if !synced.is_empty() {
+        return synced
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    normalize_breaks(text)
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !(line.starts_with('[') && line.ends_with(']')))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Render timed lines as LRC, with `[mm:ss.xx]` timestamps
+pub fn to_lrc(synced: &[LyricLine]) -> String {
+    synced
+        .iter()
+        .map(|line| {
+            let centis = line.time_ms / 10;
+            format!(
+                "[{:02}:{:02}.{:02}]{}",
+                centis / 6000,
+                centis / 100 % 60,
+                centis % 100,
+                line.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` in milliseconds; `None` when out of range,
+/// so the line is skipped like any other malformed tag
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let whole: u64 = whole.trim().parse().ok()?;
+    if whole >= 60 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let millis = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<u64>().ok()? * 100,
+        2 => fraction.parse::<u64>().ok()? * 10,
+        _ => fraction.get(..3)?.parse::<u64>().ok()?,
+    };
+    minutes
+        .checked_mul(60)?
+        .checked_add(whole)?
+        .checked_mul(1000)?
+        .checked_add(millis)
+}
+
+fn normalize_breaks(text: &str) -> String {
+    text.replace("<br />", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br>", "\n")
+        .replace("\r\n", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc_lines() {
+        let lines = parse_lrc(
+            "[ar:Arijit Singh]\n[00:12.34]Hum tere bin\n[01:02.5][00:05]Tum hi ho\nuntimed",
+        );
+        assert_eq!(
+            lines,
+            vec![
+                LyricLine {
+                    time_ms: 5_000,
+                    text: "Tum hi ho".to_string()
+                },
+                LyricLine {
+                    time_ms: 12_340,
+                    text: "Hum tere bin".to_string()
+                },
+                LyricLine {
+                    time_ms: 62_500,
+                    text: "Tum hi ho".to_string()
+                },
+            ]
+        );
+        assert_eq!(to_lrc(&lines[1..2]), "[00:12.34]Hum tere bin");
+    }
+
+    #[test]
+    fn test_parse_lrc_skips_out_of_range_times() {
+        let lines = parse_lrc("[99999999999999999:00]Too late\n[00:01]Tum hi ho");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text, "Tum hi ho");
+    }
+
+    #[test]
+    fn test_plain_from_unsynced_text() {
+        let text = "Hum tere bin<br>ab reh nahi sakte\r\n[Chorus]\nTum hi ho";
+        assert!(parse_lrc(text).is_empty());
+        assert_eq!(
+            to_plain(text, &[]),
+            "Hum tere bin\nab reh nahi sakte\nTum hi ho"
+        );
+    }
+
+    #[test]
+    fn test_extract_lyrics_text() {
+        let response = serde_json::json!({"lyrics": {"text": " Tum hi ho "}});
+        assert_eq!(extract_lyrics_text(&response).as_deref(), Some("Tum hi ho"));
+        assert_eq!(
+            extract_lyrics_text(&serde_json::json!({"lyrics": ""})),
+            None
+        );
+    }
+}
@@ -1,2 +1,13 @@
+pub mod cache;
 pub mod encryption;
+pub mod fields;
+pub mod filters;
 pub mod formatting;
+pub mod fuzzy;
+pub mod genres;
+pub mod id_index;
+pub mod languages;
+pub mod lyrics;
+pub mod query;
+pub mod recommend;
+pub mod validation;
@@ -0,0 +1,188 @@
+use crate::models::{error::ApiError, song::Song};
+use crate::utils::filters::Filterable;
+
+/// Search query split into free text and `field:value` terms, e.g.
+/// `artist:"Arijit Singh" album:aashiqui year:2013 tum hi ho`
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldedQuery {
+    pub text: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<i32>,
+}
+
+impl FieldedQuery {
+    /// Unknown fields are kept as free text, so `feat:x` still reaches Gaana
+    pub fn parse(query: &str) -> Result<Self, ApiError> {
+        let mut parsed = FieldedQuery::default();
+        let mut text = Vec::new();
+        for token in tokenize(query) {
+            let field = token
+                .split_once(':')
+                .filter(|(field, _)| !field.is_empty() && !field.contains('"'));
+            let (field, value) = match field {
+                Some((field, value)) => (field.to_ascii_lowercase(), unquote(value)),
+                None => {
+                    text.push(unquote(&token));
+                    continue;
+                }
+            };
+            if value.is_empty() {
+                return Err(ApiError::invalid_parameter(
+                    "query",
+                    &format!("Missing value for '{}:'", field),
+                ));
+            }
+            match field.as_str() {
+                "title" | "track" => parsed.title = Some(value),
+                "artist" => parsed.artist = Some(value),
+                "album" => parsed.album = Some(value),
+                "year" => {
+                    parsed.year = Some(value.parse().map_err(|_| {
+                        ApiError::invalid_parameter(
+                            "query",
+                            &format!("year must be a number, got '{}'", value),
+                        )
+                    })?)
+                }
+                _ => text.push(unquote(&token)),
+            }
+        }
+        parsed.text = text.join(" ");
+        if parsed.keyword().is_empty() {
+            return Err(ApiError::invalid_parameter(
+                "query",
+                "Needs free text or a title:, artist: or album: term to search for",
+            ));
+        }
+        Ok(parsed)
+    }
+
+    pub fn is_fielded(&self) -> bool {
+        self.title.is_some() || self.artist.is_some() || self.album.is_some() || self.year.is_some()
+    }
+
+    /// Text sent to Gaana's keyword search; falls back to the fielded values
+    pub fn keyword(&self) -> String {
+        if !self.text.is_empty() {
+            return self.text.clone();
+        }
+        [&self.title, &self.artist, &self.album]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Drop songs that contradict a fielded term and put the closest matches first
+    pub fn rank(&self, songs: &mut Vec<Song>) {
+        if !self.is_fielded() {
+            return;
+        }
+        songs.retain(|song| self.score(song).is_some());
+        songs.sort_by_key(|song| std::cmp::Reverse(self.score(song).unwrap_or(0)));
+    }
+
+    /// `None` if the song fails a term; otherwise higher for exact rather than partial matches
+    fn score(&self, song: &Song) -> Option<u32> {
+        let mut score = 0;
+        if let Some(title) = &self.title {
+            score += match_score(title, std::iter::once(song.title.as_str()))?;
+        }
+        if let Some(artist) = &self.artist {
+            score += match_score(artist, song.artists.split(','))?;
+        }
+        if let Some(album) = &self.album {
+            score += match_score(album, song.album.as_deref())?;
+        }
+        if let Some(year) = self.year {
+            if song.release_year() != Some(year) {
+                return None;
+            }
+        }
+        Some(score)
+    }
+}
+
+/// 2 for an exact (case-insensitive) match with any candidate, 1 for a substring match
+fn match_score<'a>(wanted: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<u32> {
+    let wanted = wanted.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|c| c.trim().to_lowercase())
+        .filter_map(|c| {
+            if c == wanted {
+                Some(2)
+            } else if c.contains(&wanted) {
+                Some(1)
+            } else {
+                None
+            }
+        })
+        .max()
+}
+
+/// Split on whitespace, keeping double-quoted runs (`artist:"Arijit Singh"`) together
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fielded_terms() {
+        let parsed =
+            FieldedQuery::parse(r#"artist:"Arijit Singh" album:aashiqui year:2013 tum hi ho"#)
+                .unwrap();
+        assert_eq!(parsed.artist.as_deref(), Some("Arijit Singh"));
+        assert_eq!(parsed.album.as_deref(), Some("aashiqui"));
+        assert_eq!(parsed.year, Some(2013));
+        assert_eq!(parsed.text, "tum hi ho");
+        assert_eq!(parsed.keyword(), "tum hi ho");
+    }
+
+    #[test]
+    fn test_keyword_falls_back_to_fields() {
+        let parsed = FieldedQuery::parse(r#"artist:"Arijit Singh" year:2013"#).unwrap();
+        assert_eq!(parsed.keyword(), "Arijit Singh");
+        assert!(parsed.is_fielded());
+    }
+
+    #[test]
+    fn test_plain_and_invalid_queries() {
+        let plain = FieldedQuery::parse("tyler herro").unwrap();
+        assert!(!plain.is_fielded());
+        assert_eq!(plain.text, "tyler herro");
+        assert!(FieldedQuery::parse("year:soon").is_err());
+        assert!(FieldedQuery::parse("artist:").is_err());
+        assert!(FieldedQuery::parse("year:2013").is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::models::song::Song;
+use crate::utils::filters::Filterable;
+use std::collections::VecDeque;
+
+/// Weight of each artist a candidate shares with the seed
+const SHARED_ARTIST_WEIGHT: u32 = 3;
+
+/// Weight of coming from the seed's album
+const SAME_ALBUM_WEIGHT: u32 = 2;
+
+/// Weight of each genre a candidate shares with the seed
+const SHARED_GENRE_WEIGHT: u32 = 1;
+
+/// Weight of being in the seed's language
+const SAME_LANGUAGE_WEIGHT: u32 = 1;
+
+/// How closely `candidate` relates to `seed` by shared artists, album, genres and language;
+/// 0 means unrelated
+pub fn score(seed: &Song, candidate: &Song) -> u32 {
+    let mut score = 0;
+    let seed_artists = split_list(&seed.artist_seokeys);
+    score += split_list(&candidate.artist_seokeys)
+        .iter()
+        .filter(|artist| seed_artists.contains(artist))
+        .count() as u32
+        * SHARED_ARTIST_WEIGHT;
+    if seed.album_seokey.is_some() && seed.album_seokey == candidate.album_seokey {
+        score += SAME_ALBUM_WEIGHT;
+    }
+    let seed_genres = seed.genres();
+    score += candidate
+        .genres()
+        .iter()
+        .filter(|genre| seed_genres.iter().any(|g| g.eq_ignore_ascii_case(genre)))
+        .count() as u32
+        * SHARED_GENRE_WEIGHT;
+    if let (Some(a), Some(b)) = (&seed.language, &candidate.language) {
+        if a.eq_ignore_ascii_case(b) {
+            score += SAME_LANGUAGE_WEIGHT;
+        }
+    }
+    score
+}
+
+/// Up to `limit` candidates related to `seed`, best first and then by popularity;
+/// the seed itself, repeats and unrelated songs are dropped
+pub fn rank(seed: &Song, candidates: Vec<Song>, limit: usize) -> Vec<Song> {
+    let mut seen = vec![seed.track_id.clone()];
+    let mut scored: Vec<(u32, Song)> = candidates
+        .into_iter()
+        .filter(|song| {
+            let fresh = !seen.contains(&song.track_id);
+            seen.push(song.track_id.clone());
+            fresh
+        })
+        .map(|song| (score(seed, &song), song))
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| b.1.popularity().cmp(&a.1.popularity()))
+    });
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, song)| song)
+        .collect()
+}
+
+/// Interleave songs by primary artist so no artist dominates a batch, keeping each
+/// artist's songs in order; `avoid_first` puts that artist's songs last in the rotation
+pub fn balance_artists(songs: Vec<Song>, limit: usize, avoid_first: Option<&str>) -> Vec<Song> {
+    let mut groups: Vec<(String, VecDeque<Song>)> = Vec::new();
+    for song in songs {
+        let artist = primary_artist(&song).unwrap_or_else(|| song.track_id.clone());
+        match groups.iter_mut().find(|(a, _)| *a == artist) {
+            Some((_, group)) => group.push_back(song),
+            None => groups.push((artist, VecDeque::from([song]))),
+        }
+    }
+    if let Some(position) = groups
+        .iter()
+        .position(|(a, _)| Some(a.as_str()) == avoid_first)
+    {
+        let group = groups.remove(position);
+        groups.push(group);
+    }
+
+    let mut balanced = Vec::new();
+    while balanced.len() < limit {
+        let round: Vec<Song> = groups
+            .iter_mut()
+            .filter_map(|(_, group)| group.pop_front())
+            .collect();
+        if round.is_empty() {
+            break;
+        }
+        balanced.extend(round);
+    }
+    balanced.truncate(limit);
+    balanced
+}
+
+/// First artist seokey of a song
+pub fn primary_artist(song: &Song) -> Option<String> {
+    split_list(&song.artist_seokeys).into_iter().next()
+}
+
+/// Split a comma-joined field such as `artist_seokeys` into its trimmed, non-empty entries
+pub fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(track_id: &str, artists: &str, album: &str, genres: &str) -> Song {
+        Song {
+            seokey: format!("song-{}", track_id),
+            album_seokey: Some(album.to_string()),
+            track_id: track_id.to_string(),
+            title: track_id.to_string(),
+            artists: artists.to_string(),
+            artist_seokeys: artists.to_string(),
+            artist_ids: String::new(),
+            artist_image: None,
+            album: None,
+            album_id: None,
+            duration: None,
+            popularity: None,
+            genres: Some(genres.to_string()),
+            is_explicit: None,
+            has_lyrics: None,
+            language: Some("Hindi".to_string()),
+            label: None,
+            release_date: None,
+            play_count: None,
+            favorite_count: None,
+            song_url: String::new(),
+            album_url: None,
+            images: None,
+            stream_urls: None,
+        }
+    }
+
+    #[test]
+    fn test_score_weights_shared_fields() {
+        let seed = song("1", "arijit-singh", "aashiqui-2", "Romantic");
+        let same_artist = song("2", "arijit-singh, mithoon", "other", "Pop");
+        let same_album = song("3", "ankit-tiwari", "aashiqui-2", "Romantic");
+        assert_eq!(score(&seed, &same_artist), 4);
+        assert_eq!(score(&seed, &same_album), 4);
+    }
+
+    #[test]
+    fn test_rank_drops_seed_and_repeats() {
+        let seed = song("1", "arijit-singh", "aashiqui-2", "Romantic");
+        let mut unrelated = song("4", "ksi", "dissimulation", "Rap");
+        unrelated.language = Some("English".to_string());
+        let ranked = rank(
+            &seed,
+            vec![
+                seed.clone(),
+                song("2", "arijit-singh", "other", "Pop"),
+                song("3", "ankit-tiwari", "aashiqui-2", "Romantic"),
+                song("2", "arijit-singh", "other", "Pop"),
+                unrelated,
+            ],
+            10,
+        );
+        let ids: Vec<&str> = ranked.iter().map(|s| s.track_id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+    }
+
+    #[test]
+    fn test_balance_artists_interleaves() {
+        let songs = vec![
+            song("1", "arijit-singh", "a", "Pop"),
+            song("2", "arijit-singh", "a", "Pop"),
+            song("3", "arijit-singh", "a", "Pop"),
+            song("4", "shreya-ghoshal", "b", "Pop"),
+            song("5", "atif-aslam", "c", "Pop"),
+        ];
+        let ids = |songs: Vec<Song>| songs.into_iter().map(|s| s.track_id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(balance_artists(songs.clone(), 4, None)),
+            vec!["1", "4", "5", "2"]
+        );
+        assert_eq!(
+            ids(balance_artists(songs, 3, Some("arijit-singh"))),
+            vec!["4", "5", "1"]
+        );
+    }
+}
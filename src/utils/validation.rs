@@ -0,0 +1,278 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Json, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+use crate::models::{batch::BatchRequest, error::ApiError};
+
+/// Upper bound for `limit`, since every result costs one detail call to Gaana
+pub const MAX_LIMIT: usize = 50;
+
+/// Highest page number accepted for paginated listings
+pub const MAX_PAGE: usize = 100;
+
+/// Longest search query forwarded to Gaana
+pub const MAX_QUERY_LENGTH: usize = 200;
+
+/// Most seokeys and ids accepted in one batch request
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Longest seokey accepted before we even ask Gaana
+pub const MAX_SEOKEY_LENGTH: usize = 200;
+
+/// Query parameter structs validate and normalize themselves after deserialization
+pub trait Validate {
+    fn validate(&mut self) -> Result<(), ApiError>;
+}
+
+/// Like `Query<T>`, but rejects malformed input with a 400 `ApiError` and runs `Validate`
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let deserializer =
+            serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+        let mut value: T = serde_path_to_error::deserialize(deserializer).map_err(|err| {
+            let message = err.inner().to_string();
+            let param = match err.path().to_string() {
+                path if path != "." => path,
+                _ => missing_field(&message).unwrap_or("query").to_string(),
+            };
+            ApiError::invalid_parameter(&param, &message)
+        })?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+/// serde reports a missing field at the root path, so pull its name from the message
+fn missing_field(message: &str) -> Option<&str> {
+    message.strip_prefix("missing field `")?.split('`').next()
+}
+
+/// Like `Json<T>`, but rejects malformed bodies with a 400 `ApiError` and runs `Validate`
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(mut value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| ApiError::invalid_parameter("body", &rejection.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+/// Entries are only trimmed here; malformed ones fail individually in the response
+impl Validate for BatchRequest {
+    fn validate(&mut self) -> Result<(), ApiError> {
+        for entry in self.seokeys.iter_mut().chain(self.ids.iter_mut()) {
+            *entry = entry.trim().to_string();
+        }
+        let total = self.seokeys.len() + self.ids.len();
+        if total == 0 {
+            return Err(ApiError::invalid_parameter(
+                "body",
+                "Provide at least one entry in seokeys or ids",
+            ));
+        }
+        if total > MAX_BATCH_SIZE {
+            return Err(ApiError::invalid_parameter(
+                "body",
+                &format!("At most {} seokeys and ids per request", MAX_BATCH_SIZE),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Trim a search query and reject empty or oversized input
+pub fn validate_query(param: &str, query: &str) -> Result<String, ApiError> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Err(ApiError::invalid_parameter(param, "Must not be empty"));
+    }
+    if trimmed.chars().count() > MAX_QUERY_LENGTH {
+        return Err(ApiError::invalid_parameter(
+            param,
+            &format!("Must be at most {} characters", MAX_QUERY_LENGTH),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Resolve an optional `limit` against its default and the global bound
+pub fn validate_limit(limit: Option<usize>, default: usize) -> Result<usize, ApiError> {
+    match limit {
+        None => Ok(default),
+        Some(0) => Err(ApiError::invalid_parameter(
+            "limit",
+            "Must be greater than 0",
+        )),
+        Some(n) if n > MAX_LIMIT => Err(ApiError::invalid_parameter(
+            "limit",
+            &format!("Must be at most {}", MAX_LIMIT),
+        )),
+        Some(n) => Ok(n),
+    }
+}
+
+/// Resolve an optional zero-based `page`
+pub fn validate_page(page: Option<usize>) -> Result<usize, ApiError> {
+    match page {
+        Some(n) if n > MAX_PAGE => Err(ApiError::invalid_parameter(
+            "page",
+            &format!("Must be at most {}", MAX_PAGE),
+        )),
+        Some(n) => Ok(n),
+        None => Ok(0),
+    }
+}
+
+/// Check that a seokey looks like one Gaana would issue (`tyler-herro`, `top_50`)
+pub fn validate_seokey(param: &str, seokey: &str) -> Result<String, ApiError> {
+    let trimmed = seokey.trim();
+    if trimmed.is_empty() {
+        return Err(ApiError::invalid_parameter(param, "Must not be empty"));
+    }
+    if trimmed.len() > MAX_SEOKEY_LENGTH {
+        return Err(ApiError::invalid_parameter(
+            param,
+            &format!("Must be at most {} characters", MAX_SEOKEY_LENGTH),
+        ));
+    }
+    if !trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ApiError::invalid_parameter(
+            param,
+            "May only contain letters, digits, '-' and '_'",
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Check that a numeric Gaana id (`track_id`, `album_id`, ...) is all digits
+pub fn validate_id(param: &str, id: &str) -> Result<String, ApiError> {
+    let trimmed = id.trim();
+    if trimmed.is_empty() || trimmed.len() > 20 || !trimmed.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ApiError::invalid_parameter(param, "Must be a numeric id"));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Require exactly one of `seokey` or `id`, validating whichever was given
+pub fn validate_seokey_or_id(
+    seokey: &mut Option<String>,
+    id: &mut Option<String>,
+) -> Result<(), ApiError> {
+    match (seokey.as_deref(), id.as_deref()) {
+        (Some(_), Some(_)) => Err(ApiError::invalid_parameter(
+            "id",
+            "Pass either seokey or id, not both",
+        )),
+        (None, None) => Err(ApiError::invalid_parameter(
+            "seokey",
+            "One of seokey or id is required",
+        )),
+        (Some(s), None) => {
+            *seokey = Some(validate_seokey("seokey", s)?);
+            Ok(())
+        }
+        (None, Some(i)) => {
+            *id = Some(validate_id("id", i)?);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::error::ErrorCode;
+
+    #[test]
+    fn test_validate_query_trims_and_rejects_empty() {
+        assert_eq!(validate_query("query", "  kesariya ").unwrap(), "kesariya");
+        let err = validate_query("query", "   ").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidParameter);
+        assert!(err.error.contains("query"));
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct LimitQuery {
+        #[allow(dead_code)]
+        query: String,
+        #[allow(dead_code)]
+        limit: Option<usize>,
+    }
+
+    impl Validate for LimitQuery {
+        fn validate(&mut self) -> Result<(), ApiError> {
+            Ok(())
+        }
+    }
+
+    async fn extract(uri: &str) -> Result<LimitQuery, ApiError> {
+        let request = axum::http::Request::builder().uri(uri).body(()).unwrap();
+        let (mut parts, _) = request.into_parts();
+        ValidatedQuery::<LimitQuery>::from_request_parts(&mut parts, &())
+            .await
+            .map(|ValidatedQuery(value)| value)
+    }
+
+    #[tokio::test]
+    async fn test_validated_query_names_the_bad_parameter() {
+        assert!(extract("/songs?query=x&limit=5").await.is_ok());
+        let err = extract("/songs?query=x&limit=abc").await.unwrap_err();
+        assert!(err.error.contains("limit"));
+        let err = extract("/songs?limit=5").await.unwrap_err();
+        assert!(err.error.contains("query"));
+        assert!(err.message.contains("missing field"));
+    }
+
+    #[test]
+    fn test_validate_limit_bounds() {
+        assert_eq!(validate_limit(None, 10).unwrap(), 10);
+        assert_eq!(validate_limit(Some(MAX_LIMIT), 10).unwrap(), MAX_LIMIT);
+        assert!(validate_limit(Some(0), 10).is_err());
+        assert!(validate_limit(Some(100000), 10).is_err());
+    }
+
+    #[test]
+    fn test_validate_seokey_format() {
+        assert!(validate_seokey("seokey", "gaana-dj-top-50").is_ok());
+        assert!(validate_seokey("seokey", "top_50").is_ok());
+        assert!(validate_seokey("seokey", "").is_err());
+        assert!(validate_seokey("seokey", "../etc/passwd").is_err());
+        assert!(validate_seokey("seokey", "a b").is_err());
+    }
+
+    #[test]
+    fn test_validate_seokey_or_id() {
+        let (mut seokey, mut id) = (None, Some(" 29862536 ".to_string()));
+        assert!(validate_seokey_or_id(&mut seokey, &mut id).is_ok());
+        assert_eq!(id.as_deref(), Some("29862536"));
+        assert!(validate_seokey_or_id(&mut None, &mut None).is_err());
+        assert!(validate_seokey_or_id(&mut Some("a".into()), &mut Some("1".into())).is_err());
+        assert!(validate_seokey_or_id(&mut None, &mut Some("abc".into())).is_err());
+    }
+}